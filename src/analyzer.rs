@@ -0,0 +1,429 @@
+use std::{collections::HashMap, error::Error, fs};
+
+use colored::Colorize;
+
+use crate::{dependencies::AurBuild, review::get_build_files, utils::show_message};
+
+/// Commands that can write files when given an absolute path
+const WRITE_COMMANDS: [&str; 8] = ["install", "cp", "mv", "ln", "mkdir", "touch", "tee", "rm"];
+
+const NETWORK_COMMANDS: [&str; 7] = [
+    "curl",
+    "wget",
+    "git clone",
+    "git fetch",
+    "git pull",
+    "pip install",
+    "npm install",
+];
+
+const VCS_PREFIXES: [&str; 5] = ["git+", "svn+", "hg+", "bzr+", "fossil+"];
+
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub file: String,
+    pub line: usize,
+    pub message: &'static str,
+    pub content: String,
+}
+
+/// Scans every file of the snapshot for constructs that are often used to hide malicious code
+pub fn analyze_build(build: &AurBuild) -> Result<Vec<Finding>, Box<dyn Error>> {
+    let mut findings: Vec<Finding> = vec![];
+
+    for file in get_build_files(build)? {
+        // Binary files like icons can't be scanned
+        let Ok(content) = fs::read_to_string(build.path.join(&file)) else {
+            continue;
+        };
+
+        for (index, line) in content.lines().enumerate() {
+            for message in analyze_line(line) {
+                findings.push(Finding {
+                    file: file.to_owned(),
+                    line: index + 1,
+                    message,
+                    content: line.trim().to_string(),
+                });
+            }
+        }
+
+        if file == "PKGBUILD" {
+            findings.append(&mut analyze_pkgbuild(&content));
+        }
+    }
+
+    findings.sort_by_key(|finding| {
+        (
+            finding.file != "PKGBUILD",
+            finding.file.clone(),
+            finding.line,
+        )
+    });
+
+    return Ok(findings);
+}
+
+pub fn show_findings(findings: &[Finding]) {
+    show_message(format!("{} suspicious findings:", findings.len()));
+
+    for finding in findings {
+        println!(
+            "{} {}: {}\n    {}",
+            "⚠️".yellow(),
+            format!("{}:{}", finding.file, finding.line).bold(),
+            finding.message.yellow(),
+            finding.content
+        );
+    }
+}
+
+/// Checks that apply to every file, including install scripts
+fn analyze_line(line: &str) -> Vec<&'static str> {
+    let code = strip_comment(line);
+    let mut messages: Vec<&'static str> = vec![];
+
+    if pipes_download_into_shell(code) {
+        messages.push("Downloaded content is piped into a shell");
+    }
+
+    if code.contains("base64") && (code.contains(" -d") || code.contains("--decode")) {
+        messages.push("Decodes a base64 payload");
+    }
+
+    if get_words(code).contains(&"eval") {
+        messages.push("Uses eval");
+    }
+
+    return messages;
+}
+
+/// Checks that need the structure of the PKGBUILD like its functions and arrays
+fn analyze_pkgbuild(content: &str) -> Vec<Finding> {
+    let mut findings: Vec<Finding> = vec![];
+    let mut in_package_function = false;
+
+    let mut push_finding = |index: usize, line: &str, message: &'static str| {
+        findings.push(Finding {
+            file: "PKGBUILD".to_string(),
+            line: index + 1,
+            message,
+            content: line.trim().to_string(),
+        });
+    };
+
+    for (index, line) in content.lines().enumerate() {
+        let code = strip_comment(line);
+        let trimmed = code.trim();
+
+        if trimmed.starts_with("package") && trimmed.contains("()") {
+            in_package_function = true;
+        } else if line.starts_with('}') {
+            in_package_function = false;
+        }
+
+        if writes_outside_pkgdir(trimmed) {
+            push_finding(index, line, "Writes outside of $pkgdir");
+        }
+
+        if in_package_function
+            && NETWORK_COMMANDS
+                .iter()
+                .any(|command| contains_command(trimmed, command))
+        {
+            push_finding(index, line, "Network access in package()");
+        }
+    }
+
+    let arrays = get_arrays(content);
+
+    for (name, entries) in &arrays {
+        if name.starts_with("source") {
+            for (entry, index) in entries {
+                if entry.contains("http://") {
+                    push_finding(
+                        *index,
+                        &content_line(content, *index),
+                        "Source over plain http://",
+                    );
+                }
+            }
+        }
+
+        let Some(suffix) = get_checksum_suffix(name) else {
+            continue;
+        };
+
+        let sources = arrays
+            .get(&format!("source{suffix}"))
+            .cloned()
+            .unwrap_or_default();
+
+        for (position, (entry, index)) in entries.iter().enumerate() {
+            // VCS sources can't have a checksum
+            let is_vcs = sources.get(position).is_some_and(|(source, _)| {
+                let url = source
+                    .split_once("::")
+                    .map_or(source.as_str(), |(_, url)| url);
+                VCS_PREFIXES.iter().any(|prefix| url.starts_with(prefix))
+            });
+
+            if entry == "SKIP" && !is_vcs {
+                push_finding(
+                    *index,
+                    &content_line(content, *index),
+                    "Checksum verification is skipped",
+                );
+            }
+        }
+    }
+
+    return findings;
+}
+
+fn strip_comment(line: &str) -> &str {
+    return match line.trim_start().starts_with('#') {
+        true => "",
+        false => line,
+    };
+}
+
+fn content_line(content: &str, index: usize) -> String {
+    return content.lines().nth(index).unwrap_or_default().to_string();
+}
+
+fn get_words(code: &str) -> Vec<&str> {
+    return code
+        .split(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+        .filter(|word| !word.is_empty())
+        .collect();
+}
+
+/// If the words of the command appear in order, like `git clone`
+fn contains_command(code: &str, command: &str) -> bool {
+    let words = get_words(code);
+    let command_words: Vec<&str> = command.split(' ').collect();
+
+    return words
+        .windows(command_words.len())
+        .any(|window| window == command_words.as_slice());
+}
+
+fn pipes_download_into_shell(code: &str) -> bool {
+    let segments: Vec<&str> = code.split('|').collect();
+
+    return segments.iter().enumerate().any(|(index, segment)| {
+        let downloads = contains_command(segment, "curl") || contains_command(segment, "wget");
+
+        downloads
+            && segments.iter().skip(index + 1).any(|next| {
+                let words = get_words(next);
+                let command = match words.first() {
+                    Some(&"sudo") => words.get(1),
+                    first => first,
+                };
+
+                command.is_some_and(|command| ["sh", "bash", "zsh", "dash"].contains(command))
+            })
+    });
+}
+
+/// Write commands and redirections with an absolute path that isn't inside $pkgdir or $srcdir
+fn writes_outside_pkgdir(code: &str) -> bool {
+    let is_outside = |path: &str| {
+        let path = path.trim_matches(|c| c == '"' || c == '\'');
+        path.starts_with('/') && !path.starts_with("/dev/null")
+    };
+
+    if let Some((_, target)) = code.split_once('>') {
+        let target = target.trim_start_matches(['>', '&']).trim_start();
+
+        if target.split_whitespace().next().is_some_and(is_outside) {
+            return true;
+        }
+    }
+
+    let mut words = code.split_whitespace();
+
+    let command = match words.next() {
+        Some("sudo") => words.next(),
+        command => command,
+    };
+
+    let Some(command) = command.filter(|command| WRITE_COMMANDS.contains(command)) else {
+        return false;
+    };
+
+    let mut paths = words.filter(|word| !word.starts_with('-'));
+
+    // Copying from a system path is fine as long as the destination is inside $pkgdir
+    return match command {
+        "install" | "cp" | "mv" | "ln" => paths.next_back().is_some_and(is_outside),
+        _ => paths.any(is_outside),
+    };
+}
+
+/// The entries of every array in the PKGBUILD with the index of the line they are on
+fn get_arrays(content: &str) -> HashMap<String, Vec<(String, usize)>> {
+    let mut arrays: HashMap<String, Vec<(String, usize)>> = HashMap::new();
+    let mut current: Option<String> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let code = strip_comment(line).trim();
+
+        let values = match &current {
+            Some(_) => code,
+            None => {
+                let Some((name, values)) = code.split_once("=(") else {
+                    continue;
+                };
+
+                let name = name.trim_end_matches('+');
+
+                if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                    continue;
+                }
+
+                current = Some(name.to_string());
+                values
+            }
+        };
+
+        let (values, closed) = match values.split_once(')') {
+            Some((values, _)) => (values, true),
+            None => (values, false),
+        };
+
+        if let Some(name) = &current {
+            let entries = arrays.entry(name.to_owned()).or_default();
+
+            for value in values.split_whitespace() {
+                let value = value.trim_matches(|c| c == '"' || c == '\'');
+                entries.push((value.to_string(), index));
+            }
+        }
+
+        if closed {
+            current = None;
+        }
+    }
+
+    return arrays;
+}
+
+/// The architecture suffix of checksum arrays like `sha256sums_x86_64`
+fn get_checksum_suffix(name: &str) -> Option<&str> {
+    let (kind, suffix) = name.split_at(name.find('_').unwrap_or(name.len()));
+
+    if !kind.ends_with("sums") {
+        return None;
+    }
+
+    return Some(suffix);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(content: &str) -> Vec<&'static str> {
+        return analyze_pkgbuild(content)
+            .into_iter()
+            .map(|finding| finding.message)
+            .collect();
+    }
+
+    #[test]
+    fn flags_download_piped_into_shell() {
+        assert_eq!(
+            analyze_line("curl -sL https://example.com/install.sh | sudo bash"),
+            vec!["Downloaded content is piped into a shell"]
+        );
+        assert_eq!(analyze_line("wget -qO- https://example.com | sh").len(), 1);
+    }
+
+    #[test]
+    fn ignores_download_without_shell() {
+        assert!(analyze_line("curl -o file https://example.com | tee log").is_empty());
+        assert!(analyze_line("# curl https://example.com | bash").is_empty());
+    }
+
+    #[test]
+    fn flags_base64_payload_and_eval() {
+        assert_eq!(
+            analyze_line("echo aGk= | base64 -d"),
+            vec!["Decodes a base64 payload"]
+        );
+        assert_eq!(analyze_line("eval \"$payload\""), vec!["Uses eval"]);
+        assert!(analyze_line("_evaluate=1").is_empty());
+    }
+
+    #[test]
+    fn flags_writes_outside_pkgdir() {
+        assert!(writes_outside_pkgdir("echo foo > /etc/profile"));
+        assert!(writes_outside_pkgdir("sudo cp foo /usr/bin/foo"));
+        assert!(writes_outside_pkgdir("rm -rf /home/user"));
+    }
+
+    #[test]
+    fn ignores_writes_inside_pkgdir() {
+        assert!(!writes_outside_pkgdir(
+            "install -Dm644 x \"$pkgdir/usr/share/x\""
+        ));
+        assert!(!writes_outside_pkgdir("cp /usr/share/x \"$pkgdir/x\""));
+        assert!(!writes_outside_pkgdir("make 2>&1"));
+        assert!(!writes_outside_pkgdir("command -v foo >/dev/null"));
+    }
+
+    #[test]
+    fn flags_network_access_in_package() {
+        let content = "build() {\n  git clone https://example.com/x\n}\n\npackage() {\n  curl -o x https://example.com/x\n}\n";
+
+        assert_eq!(messages(content), vec!["Network access in package()"]);
+    }
+
+    #[test]
+    fn flags_skipped_checksum_of_tarball() {
+        let content = "source=(\"https://example.com/x.tar.gz\")\nsha256sums=('SKIP')\n";
+
+        assert_eq!(messages(content), vec!["Checksum verification is skipped"]);
+    }
+
+    #[test]
+    fn ignores_skipped_checksum_of_vcs_source() {
+        let content = "source=(\"x::git+https://example.com/x.git\")\nsha256sums=('SKIP')\n";
+
+        assert!(messages(content).is_empty());
+    }
+
+    #[test]
+    fn matches_arch_checksums_to_arch_sources() {
+        let content = "source=(\"https://example.com/x.tar.gz\")
+source_x86_64=(\"git+https://example.com/x.git\")
+sha256sums=('0123')
+sha256sums_x86_64=('SKIP')
+";
+
+        assert!(messages(content).is_empty());
+        assert_eq!(get_checksum_suffix("sha256sums_x86_64"), Some("_x86_64"));
+        assert_eq!(get_checksum_suffix("source_x86_64"), None);
+    }
+
+    #[test]
+    fn flags_plain_http_source() {
+        let content = "source=(\"http://example.com/x.tar.gz\")\n";
+
+        assert_eq!(messages(content), vec!["Source over plain http://"]);
+    }
+
+    #[test]
+    fn reads_multiline_arrays() {
+        let arrays = get_arrays("depends=(\n  'foo'\n  \"bar\"\n)\nmakedepends+=(qux)\n");
+
+        assert_eq!(
+            arrays["depends"],
+            vec![("foo".to_string(), 1), ("bar".to_string(), 2)]
+        );
+        assert_eq!(arrays["makedepends"], vec![("qux".to_string(), 4)]);
+    }
+}
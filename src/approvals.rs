@@ -0,0 +1,182 @@
+use std::{
+    error::Error,
+    fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dependencies::AurBuild,
+    git::get_head_commit,
+    review::get_build_files,
+    utils::{run_hidden_in_path, show_message},
+};
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Approvals {
+    approvals: Vec<Approval>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Approval {
+    pub package_base: String,
+    pub version: String,
+
+    /// Hash of every file of the approved snapshot
+    pub hash: String,
+
+    /// The AUR commit of the approved snapshot. Local package builds don't have one.
+    pub commit: Option<String>,
+}
+
+pub fn on_reviews_list_command() -> Result<(), Box<dyn Error>> {
+    let mut approvals = read_approvals()?;
+
+    if approvals.is_empty() {
+        show_message("No approved package builds");
+        return Ok(());
+    }
+
+    approvals.sort_by(|a, b| a.package_base.cmp(&b.package_base));
+
+    for approval in approvals {
+        println!(
+            "{} {} {}",
+            approval.package_base.bold(),
+            approval.version,
+            approval.hash.chars().take(12).collect::<String>()
+        );
+    }
+
+    Ok(())
+}
+
+/// Removes the approvals so the next build of the packages is reviewed from scratch
+pub fn on_reviews_revoke_command(packages: Vec<String>) -> Result<(), Box<dyn Error>> {
+    let mut approvals = read_approvals()?;
+
+    let missing_packages: Vec<&String> = packages
+        .iter()
+        .filter(|package| {
+            !approvals
+                .iter()
+                .any(|approval| &&approval.package_base == package)
+        })
+        .collect();
+
+    approvals.retain(|approval| !packages.contains(&approval.package_base));
+    write_approvals(approvals)?;
+
+    if missing_packages.len() < packages.len() {
+        show_message("Revoked Approvals");
+    }
+
+    if !missing_packages.is_empty() {
+        show_message(format!(
+            "No approvals found for: {}",
+            missing_packages
+                .iter()
+                .map(|package| package.as_str())
+                .collect::<Vec<&str>>()
+                .join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn get_approval(pkgbase: &str) -> Result<Option<Approval>, Box<dyn Error>> {
+    return Ok(read_approvals()?
+        .into_iter()
+        .find(|approval| approval.package_base == pkgbase));
+}
+
+/// If the snapshot didn't change since it was last approved
+pub fn is_approved(build: &AurBuild, approval: &Approval) -> Result<bool, Box<dyn Error>> {
+    return Ok(approval.hash == get_snapshot_hash(build)?);
+}
+
+/// Remembers the current snapshot of the build as reviewed and approved
+pub fn approve_snapshot(build: &AurBuild) -> Result<(), Box<dyn Error>> {
+    let mut approvals = read_approvals()?;
+
+    approvals.retain(|approval| approval.package_base != build.info.package_base);
+
+    approvals.push(Approval {
+        package_base: build.info.package_base.to_owned(),
+        version: build.srcinfo.version(),
+        hash: get_snapshot_hash(build)?,
+        commit: if build.local {
+            None
+        } else {
+            Some(get_head_commit(&build.path)?)
+        },
+    });
+
+    return write_approvals(approvals);
+}
+
+/// Hashes the checksums of every file in the package build, so a change in any of them is noticed
+fn get_snapshot_hash(build: &AurBuild) -> Result<String, Box<dyn Error>> {
+    let mut files = get_build_files(build)?;
+    files.sort();
+
+    let mut command = vec!["sha256sum", "--"];
+    command.extend(files.iter().map(|file| file.as_str()));
+
+    let checksums = run_hidden_in_path(&command, &build.path)?;
+
+    let mut child = Command::new("sha256sum")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| "Failed to hash the package build".to_string())?
+        .write_all(checksums.as_bytes())?;
+
+    let output = String::from_utf8(child.wait_with_output()?.stdout)?;
+
+    let hash = output
+        .split_whitespace()
+        .next()
+        .ok_or_else(|| "Failed to hash the package build".to_string())?;
+
+    return Ok(hash.to_string());
+}
+
+fn get_approvals_path() -> Result<PathBuf, Box<dyn Error>> {
+    let data_dir = dirs::data_dir().ok_or_else(|| "Failed to get data dir".to_string())?;
+
+    return Ok(data_dir.join("aurcat").join("approvals.toml"));
+}
+
+fn read_approvals() -> Result<Vec<Approval>, Box<dyn Error>> {
+    let path = get_approvals_path()?;
+
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+
+    let approvals: Approvals = toml::from_str(&fs::read_to_string(&path)?)?;
+
+    return Ok(approvals.approvals);
+}
+
+fn write_approvals(approvals: Vec<Approval>) -> Result<(), Box<dyn Error>> {
+    let path = get_approvals_path()?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(&path, toml::to_string_pretty(&Approvals { approvals })?)?;
+
+    return Ok(());
+}
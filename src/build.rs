@@ -0,0 +1,115 @@
+use std::{
+    collections::HashMap,
+    env,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    chroot::prepare_chroot,
+    config::get_config,
+    dependencies::resolve_aur_build_order,
+    install::{
+        InstallReason, build_aur_package, get_injected_packages, install_built_packages,
+        is_package_build_dir, is_requested, review_builds, show_aur_dependencies,
+    },
+    local_repo::add_to_local_repo,
+    policy::enforce_policy,
+    utils::show_message,
+};
+
+/// Builds the packages without installing them and copies the package files to the output dir.
+/// Packages the next builds depend on are installed as dependencies on the host unless building in a chroot.
+/// With a local repository the built packages are added to it too.
+pub async fn on_build_command(
+    packages: Vec<String>,
+    output_dir: Option<PathBuf>,
+    review: bool,
+    chroot: bool,
+) -> Result<(), Box<dyn Error>> {
+    let output_dir = match output_dir {
+        Some(output_dir) => output_dir,
+        None => env::current_dir()?,
+    };
+
+    fs::create_dir_all(&output_dir)?;
+
+    let config = get_config()?;
+
+    let (local_dirs, aur_packages): (Vec<String>, Vec<String>) = packages
+        .into_iter()
+        .partition(|package| is_package_build_dir(package));
+
+    let local_dirs: Vec<PathBuf> = local_dirs.iter().map(PathBuf::from).collect();
+    let mut builds =
+        resolve_aur_build_order(&aur_packages, &local_dirs, chroot, &HashMap::new()).await?;
+
+    enforce_policy(&builds)?;
+
+    show_aur_dependencies(&builds, &aur_packages);
+
+    if !review_builds(&mut builds, review, chroot)? {
+        return Ok(());
+    }
+
+    let chroot_dir = if chroot {
+        Some(prepare_chroot()?)
+    } else {
+        None
+    };
+
+    let mut built_packages: HashMap<String, Vec<PathBuf>> = HashMap::new();
+    let mut output_files: Vec<PathBuf> = vec![];
+
+    for (index, build) in builds.iter().enumerate() {
+        let injected_packages = get_injected_packages(build, &builds, &built_packages);
+        let package_files = build_aur_package(build, chroot_dir.as_deref(), &injected_packages)?;
+
+        built_packages.insert(build.info.package_base.to_owned(), package_files.clone());
+
+        // The next builds need their dependencies installed on the host, even the requested ones
+        let install_on_host = chroot_dir.is_none()
+            && builds[index + 1..]
+                .iter()
+                .any(|next| next.dependencies.contains(&build.info.package_base));
+
+        if is_requested(build, &aur_packages) {
+            for file in &package_files {
+                output_files.push(copy_package_file(file, &output_dir)?);
+            }
+
+            // Installing adds the packages to the local repository already
+            if config.local_repo_dir().is_some() && !install_on_host {
+                add_to_local_repo(&config, &package_files)?;
+            }
+        }
+
+        if install_on_host {
+            install_built_packages(build, &package_files, Some(InstallReason::Dependency))?;
+        }
+    }
+
+    show_message("Built Packages:");
+
+    for file in &output_files {
+        println!("{}", file.display());
+    }
+
+    Ok(())
+}
+
+fn copy_package_file(file: &Path, output_dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let file_name = file
+        .file_name()
+        .ok_or_else(|| format!("Invalid package file {}", file.display()))?;
+
+    let output_file = output_dir.canonicalize()?.join(file_name);
+
+    // Copying a file onto itself would truncate it, for example when PKGDEST is the output dir
+    if file.canonicalize()? != output_file {
+        fs::copy(file, &output_file)?;
+    }
+
+    return Ok(output_file);
+}
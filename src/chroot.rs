@@ -0,0 +1,68 @@
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use crate::utils::{run_checked, run_checked_in_path, show_message};
+
+pub fn get_chroot_dir() -> PathBuf {
+    return dirs::cache_dir()
+        .expect("Failed to get cache dir")
+        .join("aurcat")
+        .join("chroot");
+}
+
+/// Creates the clean chroot if it doesn't exist yet or updates it otherwise
+pub fn prepare_chroot() -> Result<PathBuf, Box<dyn Error>> {
+    let chroot_dir = get_chroot_dir();
+    let root_dir = chroot_dir.join("root");
+
+    if root_dir.exists() {
+        show_message("Updating Clean Chroot");
+
+        run_checked(&[
+            "sudo",
+            "arch-nspawn",
+            &root_dir.display().to_string(),
+            "pacman",
+            "-Syu",
+            "--noconfirm",
+        ])?;
+    } else {
+        show_message("Creating Clean Chroot");
+
+        std::fs::create_dir_all(&chroot_dir)?;
+
+        run_checked(&[
+            "sudo",
+            "mkarchroot",
+            &root_dir.display().to_string(),
+            "base-devel",
+        ])?;
+    }
+
+    return Ok(chroot_dir);
+}
+
+/// Builds the package build in a clean copy of the chroot, installing the injected packages first
+pub fn build_in_chroot<P: AsRef<Path>>(
+    chroot_dir: &Path,
+    path: P,
+    injected_packages: &[PathBuf],
+) -> Result<(), Box<dyn Error>> {
+    let mut command = vec![
+        "makechrootpkg".to_string(),
+        "-c".to_string(),
+        "-r".to_string(),
+        chroot_dir.display().to_string(),
+    ];
+
+    for package in injected_packages {
+        command.push("-I".to_string());
+        command.push(package.display().to_string());
+    }
+
+    run_checked_in_path(&command, path)?;
+
+    return Ok(());
+}
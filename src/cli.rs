@@ -1,4 +1,6 @@
-use clap::{ArgAction, Parser, Subcommand};
+use std::path::PathBuf;
+
+use clap::{ArgAction, ArgGroup, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser, Debug)]
 pub struct Cli {
@@ -28,7 +30,56 @@ pub enum MainCommand {
         #[arg(long, group = "review_pkgbuild", action = ArgAction::SetTrue, help = "Review PKGBUILD")]
         review: bool,
 
-        #[arg(help = "The packages to install", required = false)]
+        #[arg(long, group = "chroot_build", action = ArgAction::SetTrue, help = "Build AUR packages on the host")]
+        nochroot: bool,
+
+        #[arg(long, group = "chroot_build", action = ArgAction::SetTrue, help = "Build AUR packages in a clean chroot")]
+        chroot: bool,
+
+        #[arg(long, group = "install_reason", action = ArgAction::SetTrue, help = "Mark the packages as dependencies")]
+        asdeps: bool,
+
+        #[arg(long, group = "install_reason", action = ArgAction::SetTrue, help = "Mark the packages as explicitly installed")]
+        asexplicit: bool,
+
+        #[arg(
+            long,
+            conflicts_with = "packages",
+            help = "Install the exact AUR revisions of a lockfile created with aurcat lock"
+        )]
+        locked: Option<PathBuf>,
+
+        #[arg(
+            help = "The packages to install. Use <repo>/<package> to force a repository, e.g. extra/<package> or aur/<package>. Package files and directories with a PKGBUILD are installed too",
+            required = false
+        )]
+        packages: Vec<String>,
+    },
+
+    #[command(about = "Build AUR packages without installing them")]
+    Build {
+        #[arg(
+            long,
+            help = "The directory to copy the built packages to. Defaults to the current directory"
+        )]
+        output_dir: Option<PathBuf>,
+
+        #[arg(long, group = "review_pkgbuild", action = ArgAction::SetTrue, help = "Skip PKGBUILD review")]
+        noreview: bool,
+
+        #[arg(long, group = "review_pkgbuild", action = ArgAction::SetTrue, help = "Review PKGBUILD")]
+        review: bool,
+
+        #[arg(long, group = "chroot_build", action = ArgAction::SetTrue, help = "Build AUR packages on the host")]
+        nochroot: bool,
+
+        #[arg(long, group = "chroot_build", action = ArgAction::SetTrue, help = "Build AUR packages in a clean chroot")]
+        chroot: bool,
+
+        #[arg(
+            help = "The AUR packages or directories with a PKGBUILD to build",
+            required = true
+        )]
         packages: Vec<String>,
     },
 
@@ -58,6 +109,12 @@ pub enum MainCommand {
         #[arg(long, group = "review_pkgbuild", action = ArgAction::SetTrue, help = "Review PKGBUILD")]
         review: bool,
 
+        #[arg(long, group = "chroot_build", action = ArgAction::SetTrue, help = "Build AUR packages on the host")]
+        nochroot: bool,
+
+        #[arg(long, group = "chroot_build", action = ArgAction::SetTrue, help = "Build AUR packages in a clean chroot")]
+        chroot: bool,
+
         #[arg(long, group = "confirm_update", action = ArgAction::SetTrue, help = "Skips the prompt to confirm repository packages update")]
         noconfirm: bool,
 
@@ -66,7 +123,22 @@ pub enum MainCommand {
     },
 
     #[command(about = "Search for a package")]
-    Search { package: String },
+    Search {
+        #[arg(
+            long,
+            value_enum,
+            help = "The AUR field to search by. Only the AUR is searched when it is set"
+        )]
+        by: Option<SearchBy>,
+
+        package: String,
+    },
+
+    #[command(about = "Show the details of packages from the repositories and the AUR")]
+    Info {
+        #[arg(help = "The packages to show", required = true)]
+        packages: Vec<String>,
+    },
 
     #[command(about = "List installed packages")]
     List {
@@ -77,6 +149,39 @@ pub enum MainCommand {
         filter: Option<String>,
     },
 
+    #[command(about = "Change the install reason of installed packages")]
+    #[command(group(ArgGroup::new("install_reason").required(true)))]
+    Mark {
+        #[arg(long, group = "install_reason", action = ArgAction::SetTrue, help = "Mark the packages as dependencies")]
+        asdeps: bool,
+
+        #[arg(long, group = "install_reason", action = ArgAction::SetTrue, help = "Mark the packages as explicitly installed")]
+        asexplicit: bool,
+
+        #[arg(help = "The packages to mark", required = true)]
+        packages: Vec<String>,
+    },
+
+    #[command(
+        about = "Write the versions and AUR commits of the installed AUR packages to a lockfile"
+    )]
+    Lock {
+        #[arg(long, help = "The lockfile to write. Defaults to aurcat.lock")]
+        output: Option<PathBuf>,
+    },
+
+    #[command(about = "Manage the local repository of built AUR packages")]
+    Repo {
+        #[command(subcommand)]
+        command: RepoCommand,
+    },
+
+    #[command(about = "Manage the approved package build reviews")]
+    Reviews {
+        #[command(subcommand)]
+        command: ReviewsCommand,
+    },
+
     #[command(about = "Update pacman signing keys")]
     UpdateKeys {},
 
@@ -89,3 +194,55 @@ pub enum MainCommand {
         versions: Option<usize>,
     },
 }
+
+#[derive(Subcommand, Debug)]
+pub enum RepoCommand {
+    #[command(about = "Create the local repository and print the pacman.conf snippet")]
+    Init {},
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ReviewsCommand {
+    #[command(about = "List the approved package builds")]
+    List {},
+
+    #[command(about = "Revoke approvals so the package builds are reviewed from scratch")]
+    Revoke {
+        #[arg(help = "The package bases to revoke", required = true)]
+        packages: Vec<String>,
+    },
+}
+
+/// The fields the AUR can search by
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum SearchBy {
+    Name,
+    NameDesc,
+    Maintainer,
+    Depends,
+    #[value(name = "makedepends")]
+    MakeDepends,
+    #[value(name = "optdepends")]
+    OptDepends,
+    #[value(name = "checkdepends")]
+    CheckDepends,
+    Provides,
+    Keywords,
+}
+
+impl SearchBy {
+    /// The value of the `by` parameter of the AUR RPC
+    pub fn rpc_field(&self) -> &'static str {
+        return match self {
+            SearchBy::Name => "name",
+            SearchBy::NameDesc => "name-desc",
+            SearchBy::Maintainer => "maintainer",
+            SearchBy::Depends => "depends",
+            SearchBy::MakeDepends => "makedepends",
+            SearchBy::OptDepends => "optdepends",
+            SearchBy::CheckDepends => "checkdepends",
+            SearchBy::Provides => "provides",
+            SearchBy::Keywords => "keywords",
+        };
+    }
+}
@@ -1,4 +1,4 @@
-use std::{error::Error, fs, path::PathBuf};
+use std::{env, error::Error, fs, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
 
@@ -31,6 +31,39 @@ pub struct Config {
 
     /// Fallback to pacman commands. If disabled it will use install command instead.
     pub pacman_fallback: bool,
+
+    /// Preferred repositories when a package exists on multiple ones. For example ["cachyos", "extra"]
+    pub repo_priority: Vec<String>,
+
+    /// Build AUR packages in a clean chroot with devtools
+    pub chroot: bool,
+
+    /// Review package builds with suspicious findings even when the review is disabled
+    pub force_review_on_findings: bool,
+
+    /// New AUR packages with fewer votes than this get a trust warning
+    pub trust_min_votes: usize,
+
+    /// How many days after its submission an AUR package counts as new
+    pub trust_new_package_days: u64,
+
+    /// Review package builds with trust warnings like orphaned or out of date packages, even when the review is disabled
+    pub force_review_on_trust_warnings: bool,
+
+    /// Command used to view files when reviewing. Falls back to $PAGER and then less when empty.
+    pub review_viewer: String,
+
+    /// Command used to edit package builds before building. Falls back to $EDITOR and then vi when empty.
+    pub review_editor: String,
+
+    /// Directory of a local pacman repository for the built AUR packages. Disabled when empty.
+    pub local_repo_dir: String,
+
+    /// Name of the local pacman repository
+    pub local_repo_name: String,
+
+    /// Shared directory with prebuilt AUR packages, for example on NFS. Disabled when empty.
+    pub shared_cache_dir: String,
 }
 
 impl Default for Config {
@@ -45,10 +78,71 @@ impl Default for Config {
             max_results: 30,
             pacman_fallback: false,
             confirm_update: true,
+            repo_priority: vec![],
+            chroot: false,
+            force_review_on_findings: true,
+            trust_min_votes: 10,
+            trust_new_package_days: 30,
+            force_review_on_trust_warnings: false,
+            review_viewer: String::new(),
+            review_editor: String::new(),
+            local_repo_dir: String::new(),
+            local_repo_name: "aurcat".to_string(),
+            shared_cache_dir: String::new(),
         }
     }
 }
 
+impl Config {
+    /// Position of the repository in the priority list. Repositories not listed come last.
+    pub fn repo_rank(&self, repo: &str) -> usize {
+        return self
+            .repo_priority
+            .iter()
+            .position(|priority_repo| priority_repo == repo)
+            .unwrap_or(self.repo_priority.len());
+    }
+
+    pub fn local_repo_dir(&self) -> Option<PathBuf> {
+        return match self.local_repo_dir.trim() {
+            "" => None,
+            dir => Some(PathBuf::from(dir)),
+        };
+    }
+
+    pub fn shared_cache_dir(&self) -> Option<PathBuf> {
+        return match self.shared_cache_dir.trim() {
+            "" => None,
+            dir => Some(PathBuf::from(dir)),
+        };
+    }
+
+    pub fn viewer_command(&self) -> Vec<String> {
+        return get_command(&self.review_viewer, "PAGER", "less");
+    }
+
+    pub fn editor_command(&self) -> Vec<String> {
+        return get_command(&self.review_editor, "EDITOR", "vi");
+    }
+
+    /// The preferred repository out of the ones that contain a package
+    pub fn preferred_repo<'a>(&self, repos: &'a [String]) -> Option<&'a String> {
+        return repos.iter().min_by_key(|repo| self.repo_rank(repo));
+    }
+}
+
+fn get_command(command: &str, env_var: &str, fallback: &str) -> Vec<String> {
+    let command = match command.trim() {
+        "" => env::var(env_var)
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| fallback.to_string()),
+        command => command.to_string(),
+    };
+
+    return command.split_whitespace().map(|s| s.to_string()).collect();
+}
+
 fn get_config_path() -> Result<PathBuf, Box<dyn Error>> {
     let config = dirs::config_dir().ok_or_else(|| "Failed to get config dir".to_string())?;
 
@@ -0,0 +1,223 @@
+use std::{error::Error, fs, path::Path};
+
+use colored::Colorize;
+
+use crate::{
+    dependencies::AurBuild,
+    list::get_all_packages,
+    srcinfo::dependency_name,
+    utils::{prompt_confirm, run_hidden, show_message},
+};
+
+/// Checks the conflicts, replaces and provides of the builds against the installed packages.
+/// Conflicts and replaces also match what the installed packages provide, like pacman does.
+/// Returns false if the user cancels.
+pub fn check_conflicts(builds: &[AurBuild]) -> Result<bool, Box<dyn Error>> {
+    let installed_packages = get_all_packages()?;
+    let installed_provides = get_installed_provides()?;
+    let mut removed_packages: Vec<String> = vec![];
+    let mut replaced_packages: Vec<String> = vec![];
+    let mut provided_packages: Vec<String> = vec![];
+
+    // Every installed package with its own version and every package it provides
+    let installed_names: Vec<(&str, &str, Option<&str>)> = installed_packages
+        .iter()
+        .map(|query| {
+            (
+                query.package.as_str(),
+                query.package.as_str(),
+                Some(query.version.as_str()),
+            )
+        })
+        .chain(installed_provides.iter().map(|(package, provide)| {
+            let name = dependency_name(provide);
+            let version = provide[name.len()..].strip_prefix('=');
+
+            (package.as_str(), name, version)
+        }))
+        .collect();
+
+    for build in builds {
+        for pkgname in &build.pkgnames {
+            let is_other_package = |package: &str| {
+                package != pkgname && !build.pkgnames.iter().any(|name| name == package)
+            };
+
+            for key in ["conflicts", "replaces"] {
+                for value in build.srcinfo.package_values(pkgname, key) {
+                    let installed = installed_names.iter().find(|(package, name, version)| {
+                        is_other_package(package) && matches_installed(&value, name, *version)
+                    });
+
+                    let Some((package, name, _)) = installed else {
+                        continue;
+                    };
+
+                    let provided = if package == name {
+                        String::new()
+                    } else {
+                        format!(", provides {name}")
+                    };
+
+                    let (messages, message) = match key {
+                        "replaces" => (
+                            &mut replaced_packages,
+                            format!(
+                                "{} (replaced by {pkgname}{provided})",
+                                package.yellow().bold()
+                            ),
+                        ),
+                        _ => (
+                            &mut removed_packages,
+                            format!(
+                                "{} (conflicts with {pkgname}{provided})",
+                                package.red().bold()
+                            ),
+                        ),
+                    };
+
+                    if !messages.contains(&message) {
+                        messages.push(message);
+                    }
+                }
+            }
+
+            for value in build.srcinfo.package_values(pkgname, "provides") {
+                let installed = installed_packages
+                    .iter()
+                    .filter(|query| is_other_package(&query.package))
+                    .find(|query| matches_installed(&value, &query.package, Some(&query.version)));
+
+                if let Some(installed) = installed {
+                    let message = format!(
+                        "{} (provided by {pkgname})",
+                        installed.package.yellow().bold()
+                    );
+
+                    if !provided_packages.contains(&message) {
+                        provided_packages.push(message);
+                    }
+                }
+            }
+        }
+    }
+
+    if removed_packages.is_empty() && replaced_packages.is_empty() && provided_packages.is_empty() {
+        return Ok(true);
+    }
+
+    if !removed_packages.is_empty() {
+        show_message("These installed packages would have to be removed:");
+
+        for message in &removed_packages {
+            println!("{message}");
+        }
+    }
+
+    if !replaced_packages.is_empty() {
+        show_message(
+            "These installed packages are replaced by the new packages, but stay installed until removed:",
+        );
+
+        for message in &replaced_packages {
+            println!("{message}");
+        }
+    }
+
+    if !provided_packages.is_empty() {
+        show_message("These installed packages are also provided by the new packages:");
+
+        for message in &provided_packages {
+            println!("{message}");
+        }
+    }
+
+    return prompt_confirm("Would you like to continue?", true);
+}
+
+/// If a conflicts, replaces or provides entry like `foo<2.0` matches an installed name.
+/// Provides without a version only match entries without a version constraint.
+fn matches_installed(value: &str, installed_name: &str, installed_version: Option<&str>) -> bool {
+    let name = dependency_name(value);
+
+    if name != installed_name {
+        return false;
+    }
+
+    let constraint = &value[name.len()..];
+
+    if constraint.is_empty() {
+        return true;
+    }
+
+    let Some(installed_version) = installed_version else {
+        return false;
+    };
+
+    let operator_len = constraint
+        .chars()
+        .take_while(|c| ['<', '>', '='].contains(c))
+        .count();
+
+    let (operator, version) = constraint.split_at(operator_len);
+
+    let Ok(output) = run_hidden(&["vercmp", installed_version, version]) else {
+        return true;
+    };
+
+    let Ok(ordering) = output.trim().parse::<i32>() else {
+        return true;
+    };
+
+    return match operator {
+        "<" => ordering < 0,
+        "<=" => ordering <= 0,
+        ">" => ordering > 0,
+        ">=" => ordering >= 0,
+        _ => ordering == 0,
+    };
+}
+
+/// The provides of every installed package, read from the local database
+fn get_installed_provides() -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    let db_path = run_hidden(&["pacman-conf", "DBPath"])?;
+    let db_path = match db_path.trim() {
+        "" => "/var/lib/pacman/",
+        db_path => db_path,
+    };
+
+    let local_dir = Path::new(db_path).join("local");
+    let mut provides: Vec<(String, String)> = vec![];
+
+    if !local_dir.is_dir() {
+        return Ok(provides);
+    }
+
+    for entry in fs::read_dir(local_dir)? {
+        let Ok(content) = fs::read_to_string(entry?.path().join("desc")) else {
+            continue;
+        };
+
+        let mut section = "";
+        let mut package = "";
+
+        for line in content.lines() {
+            if line.starts_with('%') && line.ends_with('%') {
+                section = line;
+                continue;
+            }
+
+            if line.is_empty() {
+                continue;
+            }
+
+            match section {
+                "%NAME%" => package = line,
+                "%PROVIDES%" => provides.push((package.to_string(), line.to_string())),
+                _ => {}
+            }
+        }
+    }
+
+    return Ok(provides);
+}
@@ -0,0 +1,440 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{
+    git::{checkout_commit, get_head_commit, get_installed_commit, sync_aur_repo},
+    install::{AurPackageInfoResult, get_aur_packages_info},
+    providers::{get_providers, pick_provider},
+    srcinfo::{SrcInfo, dependency_name},
+    utils::run_hidden,
+};
+
+#[derive(Debug, Clone)]
+pub struct AurBuild {
+    pub info: AurPackageInfoResult,
+    pub srcinfo: SrcInfo,
+    pub path: PathBuf,
+
+    /// The split packages of the package base that should be installed
+    pub pkgnames: Vec<String>,
+
+    /// Package bases of the AUR dependencies
+    pub dependencies: Vec<String>,
+
+    /// The commit of the last installed build
+    pub installed_commit: Option<String>,
+
+    /// If the package build changed since it was last installed
+    pub changed: bool,
+
+    /// If the package build is a local directory instead of an AUR repository
+    pub local: bool,
+
+    /// If the package build was edited during the review, so it has to be rebuilt
+    pub edited: bool,
+}
+
+/// Downloads the packages and every dependency only available in the AUR and returns them in build order.
+/// Split packages are grouped by their package base so they are only built once.
+/// Local package build directories are built with all their split packages.
+/// In chroot mode the installed packages are ignored since the chroot only has the repositories.
+/// Package bases with a locked commit are built from that commit instead of the latest one.
+pub async fn resolve_aur_build_order<S: AsRef<str>>(
+    packages: &[S],
+    local_dirs: &[PathBuf],
+    chroot: bool,
+    locked_commits: &HashMap<String, String>,
+) -> Result<Vec<AurBuild>, Box<dyn Error>> {
+    let mut builds: HashMap<String, AurBuild> = HashMap::new();
+    let mut edges: HashMap<String, Vec<String>> = HashMap::new();
+    let mut roots: Vec<String> = vec![];
+    let mut queue: Vec<(String, Option<String>)> = vec![];
+
+    for local_dir in local_dirs {
+        let build = read_local_build(local_dir)?;
+        let pkgbase = build.info.package_base.to_owned();
+        let pkgnames: Vec<&str> = build.pkgnames.iter().map(|name| name.as_str()).collect();
+
+        let aur_dependencies = get_aur_dependencies(
+            build.srcinfo.dependencies(&pkgnames),
+            &build.srcinfo.pkgnames(),
+            chroot,
+        )?;
+
+        for dependency in &aur_dependencies {
+            queue.push((dependency.to_owned(), Some(pkgbase.to_owned())));
+        }
+
+        edges.insert(pkgbase.to_owned(), aur_dependencies);
+        roots.push(pkgbase.to_owned());
+        builds.insert(pkgbase, build);
+    }
+
+    // Virtual packages and the package that provides them
+    let mut aliases: HashMap<String, String> = HashMap::new();
+
+    queue.extend(
+        packages
+            .iter()
+            .rev()
+            .map(|package| (package.as_ref().to_string(), None)),
+    );
+
+    while let Some((name, required_by)) = queue.pop() {
+        if aliases.contains_key(&name)
+            || builds.values().any(|build| build.pkgnames.contains(&name))
+        {
+            continue;
+        }
+
+        if let Some(provider) = get_build_provider(&builds, &name) {
+            aliases.insert(name, provider);
+            continue;
+        }
+
+        let mut info = match get_aur_packages_info(&[&name]).await?.into_iter().next() {
+            Some(info) => info,
+            None => {
+                // Virtual packages are resolved to an AUR package that provides them
+                let providers = get_providers(&name, false).await?;
+
+                let provider =
+                    pick_provider(&name, providers)?.ok_or_else(|| match &required_by {
+                        Some(parent) => {
+                            format!("Failed to find dependency {name} of {parent} in the AUR")
+                        }
+                        None => format!("Failed to find {name} in the AUR"),
+                    })?;
+
+                aliases.insert(name, provider.package.to_owned());
+                queue.push((provider.package, required_by));
+                continue;
+            }
+        };
+
+        let pkgbase = info.package_base.to_owned();
+
+        if required_by.is_none() && !roots.contains(&pkgbase) {
+            roots.push(pkgbase.to_owned());
+        }
+
+        let dependencies = match builds.get_mut(&pkgbase) {
+            Some(build) => {
+                build.pkgnames.push(name.to_owned());
+                build.srcinfo.package_values(&name, "depends")
+            }
+            None => {
+                let path = sync_aur_repo(&info.package_base)?;
+
+                if let Some(commit) = locked_commits.get(&pkgbase) {
+                    checkout_commit(&path, commit)?;
+                }
+
+                let installed_commit = get_installed_commit(&path);
+                let changed = installed_commit.as_ref() != Some(&get_head_commit(&path)?);
+                let srcinfo = SrcInfo::read(&path)?;
+
+                // Locked commits can be older than the version in the AUR
+                if locked_commits.contains_key(&pkgbase) {
+                    info.version = srcinfo.version();
+                }
+
+                let dependencies = srcinfo.dependencies(&[&name]);
+
+                builds.insert(
+                    pkgbase.to_owned(),
+                    AurBuild {
+                        info,
+                        srcinfo,
+                        path,
+                        pkgnames: vec![name.to_owned()],
+                        dependencies: vec![],
+                        installed_commit,
+                        changed,
+                        local: false,
+                        edited: false,
+                    },
+                );
+
+                dependencies
+            }
+        };
+
+        let aur_dependencies =
+            get_aur_dependencies(dependencies, &builds[&pkgbase].srcinfo.pkgnames(), chroot)?;
+
+        for dependency in &aur_dependencies {
+            queue.push((dependency.to_owned(), Some(name.to_owned())));
+        }
+
+        edges.entry(pkgbase).or_default().extend(aur_dependencies);
+    }
+
+    // Edges point to package names, the build order is sorted by package base
+    let pkgbases: HashMap<String, String> = builds
+        .iter()
+        .flat_map(|(pkgbase, build)| {
+            build
+                .pkgnames
+                .iter()
+                .map(|name| (name.to_owned(), pkgbase.to_owned()))
+        })
+        .collect();
+
+    let edges: HashMap<String, Vec<String>> = edges
+        .into_iter()
+        .map(|(pkgbase, dependencies)| {
+            let dependencies = dependencies
+                .iter()
+                .map(|dependency| aliases.get(dependency).unwrap_or(dependency))
+                .filter_map(|dependency| pkgbases.get(dependency).cloned())
+                .collect();
+
+            (pkgbase, dependencies)
+        })
+        .collect();
+
+    let order = sort_build_order(&roots, &edges)?;
+
+    for (pkgbase, dependencies) in edges {
+        if let Some(build) = builds.get_mut(&pkgbase) {
+            build.dependencies = dependencies;
+        }
+    }
+
+    return Ok(order
+        .iter()
+        .filter_map(|pkgbase| builds.remove(pkgbase))
+        .collect());
+}
+
+/// Dependencies that have to be built from the AUR. Split packages of the same package base are skipped.
+fn get_aur_dependencies(
+    dependencies: Vec<String>,
+    split_packages: &[&str],
+    chroot: bool,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let missing_dependencies = if chroot {
+        dependencies
+    } else {
+        get_missing_dependencies(&dependencies)?
+    };
+
+    return Ok(missing_dependencies
+        .into_iter()
+        .map(|dependency| dependency_name(&dependency).to_string())
+        .filter(|dependency| !split_packages.contains(&dependency.as_str()))
+        .filter(|dependency| !is_repo_dependency(dependency))
+        .collect());
+}
+
+/// Reads a local directory with a PKGBUILD as a build of all its split packages
+pub fn read_local_build(path: &Path) -> Result<AurBuild, Box<dyn Error>> {
+    let path = path.canonicalize()?;
+    let srcinfo = SrcInfo::generate(&path)?;
+
+    if srcinfo.pkgbase().is_empty() {
+        return Err(format!("Failed to read the PKGBUILD in {}", path.display()).into());
+    }
+
+    let pkgnames: Vec<String> = srcinfo
+        .pkgnames()
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+
+    let info = AurPackageInfoResult {
+        last_modified: 0,
+        url_path: String::new(),
+        version: srcinfo.version(),
+        name: pkgnames.first().cloned().unwrap_or_default(),
+        package_base: srcinfo.pkgbase().to_string(),
+        ..Default::default()
+    };
+
+    return Ok(AurBuild {
+        info,
+        srcinfo,
+        path,
+        pkgnames,
+        dependencies: vec![],
+        installed_commit: None,
+        changed: true,
+        local: true,
+        edited: false,
+    });
+}
+
+/// Edited package builds can depend on AUR packages that weren't resolved.
+/// Returns an error if a dependency isn't built before the edited build.
+pub fn check_edited_dependencies(builds: &[AurBuild], chroot: bool) -> Result<(), Box<dyn Error>> {
+    for (index, build) in builds.iter().enumerate() {
+        if !build.edited {
+            continue;
+        }
+
+        let pkgnames: Vec<&str> = build.pkgnames.iter().map(|name| name.as_str()).collect();
+
+        let missing_dependencies: Vec<String> = get_aur_dependencies(
+            build.srcinfo.dependencies(&pkgnames),
+            &build.srcinfo.pkgnames(),
+            chroot,
+        )?
+        .into_iter()
+        .filter(|dependency| {
+            !builds[..index]
+                .iter()
+                .any(|previous| provides(previous, dependency))
+        })
+        .collect();
+
+        if !missing_dependencies.is_empty() {
+            return Err(format!(
+                "The edited {} depends on {} from the AUR, install them first",
+                &build.info.package_base,
+                missing_dependencies.join(", ")
+            )
+            .into());
+        }
+    }
+
+    return Ok(());
+}
+
+/// If one of the packages of the build is or provides the name
+fn provides(build: &AurBuild, name: &str) -> bool {
+    return build.pkgnames.iter().any(|pkgname| {
+        pkgname == name
+            || build
+                .srcinfo
+                .package_values(pkgname, "provides")
+                .iter()
+                .any(|provide| dependency_name(provide) == name)
+    });
+}
+
+/// A requested package of the builds that provides the name
+fn get_build_provider(builds: &HashMap<String, AurBuild>, name: &str) -> Option<String> {
+    return builds.values().find_map(|build| {
+        build
+            .pkgnames
+            .iter()
+            .find(|pkgname| {
+                build
+                    .srcinfo
+                    .package_values(pkgname, "provides")
+                    .iter()
+                    .any(|provide| dependency_name(provide) == name)
+            })
+            .cloned()
+    });
+}
+
+/// Dependencies that aren't satisfied by the installed packages
+pub fn get_missing_dependencies(dependencies: &[String]) -> Result<Vec<String>, Box<dyn Error>> {
+    if dependencies.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut command = vec!["pacman", "-T"];
+    command.extend(dependencies.iter().map(|dependency| dependency.as_str()));
+
+    let output = run_hidden(&command)?;
+
+    return Ok(output.lines().map(|line| line.trim().to_string()).collect());
+}
+
+/// If a dependency can be satisfied by the sync databases
+pub fn is_repo_dependency(dependency: &str) -> bool {
+    return Command::new("pacman")
+        .args(["-Sp", "--print-format", "%n", dependency])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+}
+
+fn sort_build_order(
+    roots: &[String],
+    edges: &HashMap<String, Vec<String>>,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut order: Vec<String> = vec![];
+    let mut visiting: Vec<String> = vec![];
+    let mut visited: HashSet<String> = HashSet::new();
+
+    for root in roots {
+        visit(root, edges, &mut visiting, &mut visited, &mut order)?;
+    }
+
+    return Ok(order);
+}
+
+fn visit(
+    package: &str,
+    edges: &HashMap<String, Vec<String>>,
+    visiting: &mut Vec<String>,
+    visited: &mut HashSet<String>,
+    order: &mut Vec<String>,
+) -> Result<(), Box<dyn Error>> {
+    if visited.contains(package) {
+        return Ok(());
+    }
+
+    if let Some(position) = visiting.iter().position(|name| name == package) {
+        let mut cycle = visiting[position..].to_vec();
+        cycle.push(package.to_string());
+
+        return Err(format!("Dependency cycle detected: {}", cycle.join(" -> ")).into());
+    }
+
+    visiting.push(package.to_string());
+
+    for dependency in edges.get(package).into_iter().flatten() {
+        visit(dependency, edges, visiting, visited, order)?;
+    }
+
+    visiting.pop();
+    visited.insert(package.to_string());
+    order.push(package.to_string());
+
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(pairs: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        return pairs
+            .iter()
+            .map(|(package, dependencies)| {
+                (
+                    package.to_string(),
+                    dependencies.iter().map(|name| name.to_string()).collect(),
+                )
+            })
+            .collect();
+    }
+
+    #[test]
+    fn sorts_dependencies_first() {
+        let edges = edges(&[("a", &["b", "c"]), ("b", &["c"]), ("c", &[])]);
+        let order = sort_build_order(&["a".to_string()], &edges).unwrap();
+
+        assert_eq!(order, vec!["c", "b", "a"]);
+    }
+
+    #[test]
+    fn reports_the_full_cycle() {
+        let edges = edges(&[("a", &["b"]), ("b", &["c"]), ("c", &["d"]), ("d", &["b"])]);
+        let error = sort_build_order(&["a".to_string()], &edges).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Dependency cycle detected: b -> c -> d -> b"
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::utils::{run, run_hidden_in_path, show_message};
+
+/// Ref pointing to the commit of the last installed build
+const INSTALLED_REF: &str = "refs/aurcat/installed";
+
+/// Ref that exists while the package files in the working tree were built from an edited snapshot
+const EDITED_REF: &str = "refs/aurcat/edited";
+
+/// The clones live in their own directory so a package base can't collide with the chroot
+pub fn get_aur_repo_dir(pkgbase: &str) -> PathBuf {
+    return dirs::cache_dir()
+        .expect("Failed to get cache dir")
+        .join("aurcat")
+        .join("repos")
+        .join(pkgbase);
+}
+
+/// Clones the AUR repository of the package base or fetches it if it already exists.
+/// The working tree is reset to the upstream commit.
+pub fn sync_aur_repo(pkgbase: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let repo_dir = get_aur_repo_dir(pkgbase);
+
+    // A failed clone can leave a directory without a repository
+    if repo_dir.exists() && !repo_dir.join(".git").exists() {
+        fs::remove_dir_all(&repo_dir)?;
+    }
+
+    if repo_dir.exists() {
+        show_message(format!("Fetching Package Build: {pkgbase}"));
+
+        run_git(&repo_dir, &["fetch", "--quiet", "origin"])?;
+        run_git(&repo_dir, &["reset", "--quiet", "--hard", "origin/HEAD"])?;
+    } else {
+        show_message(format!("Cloning Package Build: {pkgbase}"));
+
+        let parent_dir = repo_dir
+            .parent()
+            .ok_or_else(|| "Failed to get cache dir".to_string())?;
+
+        fs::create_dir_all(parent_dir)?;
+
+        run(&[
+            "git",
+            "clone",
+            "--quiet",
+            &format!("https://aur.archlinux.org/{pkgbase}.git"),
+            &repo_dir.display().to_string(),
+        ])?;
+
+        if !repo_dir.join(".git").exists() {
+            return Err(format!("Failed to clone {pkgbase}").into());
+        }
+    }
+
+    return Ok(repo_dir);
+}
+
+pub fn get_head_commit<P: AsRef<Path>>(path: P) -> Result<String, Box<dyn Error>> {
+    let output = run_hidden_in_path(&["git", "rev-parse", "HEAD"], path)?;
+
+    return Ok(output.trim().to_string());
+}
+
+/// The commit of the last installed build, if it was installed by aurcat
+pub fn get_installed_commit<P: AsRef<Path>>(path: P) -> Option<String> {
+    let output =
+        run_hidden_in_path(&["git", "rev-parse", "--verify", "-q", INSTALLED_REF], path).ok()?;
+
+    let commit = output.trim();
+
+    if commit.is_empty() {
+        return None;
+    }
+
+    return Some(commit.to_string());
+}
+
+pub fn mark_installed<P: AsRef<Path>>(path: P) -> Result<(), Box<dyn Error>> {
+    run_git(path, &["update-ref", INSTALLED_REF, "HEAD"])?;
+
+    return Ok(());
+}
+
+/// Records that the package files in the working tree were built from an edited snapshot
+pub fn mark_edited_build<P: AsRef<Path>>(path: P) -> Result<(), Box<dyn Error>> {
+    run_git(path, &["update-ref", EDITED_REF, "HEAD"])?;
+
+    return Ok(());
+}
+
+/// Records that the package files in the working tree were built from the upstream snapshot
+pub fn clear_edited_build<P: AsRef<Path>>(path: P) -> Result<(), Box<dyn Error>> {
+    run_git(path, &["update-ref", "-d", EDITED_REF])?;
+
+    return Ok(());
+}
+
+/// If the package files in the working tree were built from an edited snapshot.
+/// The reset to upstream keeps them, so they have to be rebuilt.
+pub fn has_edited_build<P: AsRef<Path>>(path: P) -> bool {
+    return run_hidden_in_path(&["git", "rev-parse", "--verify", "-q", EDITED_REF], path)
+        .is_ok_and(|output| !output.trim().is_empty());
+}
+
+/// Resets the working tree to an older commit of the repository
+pub fn checkout_commit<P: AsRef<Path>>(path: P, commit: &str) -> Result<(), Box<dyn Error>> {
+    run_git(path, &["reset", "--quiet", "--hard", commit])?;
+
+    return Ok(());
+}
+
+/// The commits that changed the file, newest first
+pub fn get_file_commits<P: AsRef<Path>>(
+    path: P,
+    file: &str,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let output = run_hidden_in_path(&["git", "log", "--format=%H", "HEAD", "--", file], path)?;
+
+    return Ok(output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect());
+}
+
+/// The content of the file at the commit
+pub fn get_file_at_commit<P: AsRef<Path>>(
+    path: P,
+    commit: &str,
+    file: &str,
+) -> Result<String, Box<dyn Error>> {
+    return run_hidden_in_path(&["git", "show", &format!("{commit}:{file}")], path);
+}
+
+/// The unix time of the commit
+pub fn get_commit_time<P: AsRef<Path>>(path: P, commit: &str) -> Result<usize, Box<dyn Error>> {
+    let output = run_hidden_in_path(&["git", "show", "-s", "--format=%ct", commit], path)?;
+
+    return Ok(output.trim().parse()?);
+}
+
+/// The files tracked in the repository, without the build leftovers
+pub fn get_repo_files<P: AsRef<Path>>(path: P) -> Result<Vec<String>, Box<dyn Error>> {
+    let output = run_hidden_in_path(&["git", "ls-files"], path)?;
+
+    return Ok(output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect());
+}
+
+/// If a tracked file differs from HEAD. Untracked build leftovers are ignored.
+pub fn has_local_changes<P: AsRef<Path>>(path: P) -> Result<bool, Box<dyn Error>> {
+    let output = run_hidden_in_path(
+        &["git", "status", "--porcelain", "--untracked-files=no"],
+        path,
+    )?;
+
+    return Ok(!output.trim().is_empty());
+}
+
+/// Shows the changes of every file between the commit and HEAD
+pub fn show_diff<P: AsRef<Path>>(path: P, from: &str) -> Result<(), Box<dyn Error>> {
+    run_git_visible(
+        path,
+        &["--no-pager", "diff", "--color=always", from, "HEAD"],
+    )?;
+
+    return Ok(());
+}
+
+fn run_git<P: AsRef<Path>>(path: P, args: &[&str]) -> Result<(), Box<dyn Error>> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(path.as_ref())
+        .output()?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    return Ok(());
+}
+
+fn run_git_visible<P: AsRef<Path>>(path: P, args: &[&str]) -> Result<(), Box<dyn Error>> {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(path.as_ref())
+        .status()?;
+
+    if !status.success() {
+        return Err(format!("git {} failed", args.join(" ")).into());
+    }
+
+    return Ok(());
+}
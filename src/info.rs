@@ -0,0 +1,302 @@
+use std::{collections::HashMap, error::Error, process::Command};
+
+use colored::Colorize;
+use convert_case::{Case, Casing};
+
+use crate::{
+    config::get_config,
+    install::{AurPackageInfoResult, get_aur_packages_info},
+    utils::show_message,
+};
+
+/// Details of a package from the sync databases or the AUR, merged with the installed version
+#[derive(Debug, Default)]
+struct PackageDetails {
+    /// The repository of the package, `aur` or `local` if it is only installed
+    repo: String,
+    name: String,
+    installed_version: Option<String>,
+    latest_version: Option<String>,
+    description: Option<String>,
+    url: Option<String>,
+    licenses: Vec<String>,
+    depends: Vec<String>,
+    make_depends: Vec<String>,
+    opt_depends: Vec<String>,
+    provides: Vec<String>,
+    conflicts: Vec<String>,
+    maintainer: Option<String>,
+    votes: Option<usize>,
+    popularity: Option<f64>,
+    out_of_date: Option<usize>,
+}
+
+pub async fn on_info_command(packages: Vec<String>) -> Result<(), Box<dyn Error>> {
+    let config = get_config()?;
+    let aur_infos = get_aur_packages_info(&packages).await?;
+    let mut missing_packages: Vec<String> = vec![];
+
+    for package in &packages {
+        let installed = get_pacman_info("-Qi", package)?.into_iter().next();
+        let repo_infos = get_pacman_info("-Si", package)?;
+
+        let repos: Vec<String> = repo_infos
+            .iter()
+            .filter_map(|info| info.get("Repository").cloned())
+            .collect();
+
+        let repo_info = config.preferred_repo(&repos).and_then(|repo| {
+            repo_infos
+                .iter()
+                .find(|info| info.get("Repository") == Some(repo))
+        });
+
+        let aur_info = aur_infos.iter().find(|info| &info.name == package);
+
+        let mut details = match (repo_info, aur_info, &installed) {
+            (Some(repo_info), _, _) => from_pacman_info(repo_info),
+            (None, Some(aur_info), _) => from_aur_info(aur_info),
+            (None, None, Some(installed)) => PackageDetails {
+                repo: "local".to_string(),
+                ..from_pacman_info(installed)
+            },
+            (None, None, None) => {
+                missing_packages.push(package.to_owned());
+                continue;
+            }
+        };
+
+        details.installed_version = installed.and_then(|info| info.get("Version").cloned());
+
+        show_details(&details);
+    }
+
+    if !missing_packages.is_empty() {
+        show_message(format!(
+            "Packages not found: {}",
+            missing_packages.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+fn show_details(details: &PackageDetails) {
+    let repo = match details.repo.as_str() {
+        "aur" => "AUR".blue().bold(),
+        repo => repo.to_case(Case::Upper).green().bold(),
+    };
+
+    println!("{} {}", repo, details.name.bold());
+
+    let version = match (&details.installed_version, &details.latest_version) {
+        (Some(installed), Some(latest)) if installed != latest => Some(format!(
+            "{} (latest {})",
+            installed.yellow(),
+            latest.green()
+        )),
+        (Some(installed), _) => Some(format!("{installed} (installed)")),
+        (None, Some(latest)) => Some(latest.to_owned()),
+        (None, None) => None,
+    };
+
+    print_field("Version", version.as_deref());
+    print_field("Description", details.description.as_deref());
+    print_field("URL", details.url.as_deref());
+    print_list("Licenses", &details.licenses);
+    print_list("Depends", &details.depends);
+    print_list("Make Depends", &details.make_depends);
+    print_list("Optional Depends", &details.opt_depends);
+    print_list("Provides", &details.provides);
+    print_list("Conflicts", &details.conflicts);
+
+    if details.repo == "aur" {
+        print_field(
+            "Maintainer",
+            Some(details.maintainer.as_deref().unwrap_or("nobody (orphaned)")),
+        );
+    } else {
+        print_field("Maintainer", details.maintainer.as_deref());
+    }
+
+    print_field(
+        "Votes",
+        details.votes.map(|votes| votes.to_string()).as_deref(),
+    );
+
+    print_field(
+        "Popularity",
+        details
+            .popularity
+            .map(|popularity| format!("{popularity:.2}"))
+            .as_deref(),
+    );
+
+    if let Some(out_of_date) = details.out_of_date {
+        print_field(
+            "Out of Date",
+            Some(&format_date(out_of_date).red().to_string()),
+        );
+    }
+
+    println!();
+}
+
+fn print_field(name: &str, value: Option<&str>) {
+    if let Some(value) = value.filter(|value| !value.is_empty()) {
+        println!("{:<18}{}", format!("{name}:").bold(), value);
+    }
+}
+
+fn print_list(name: &str, values: &[String]) {
+    print_field(name, Some(&values.join("  ")));
+}
+
+fn from_pacman_info(info: &HashMap<String, String>) -> PackageDetails {
+    let get_list = |key: &str| -> Vec<String> {
+        match info.get(key) {
+            Some(value) if key == "Optional Deps" => {
+                value.lines().map(|line| line.trim().to_string()).collect()
+            }
+            Some(value) => value
+                .split_whitespace()
+                .map(|value| value.to_string())
+                .collect(),
+            None => vec![],
+        }
+    };
+
+    return PackageDetails {
+        repo: info.get("Repository").cloned().unwrap_or_default(),
+        name: info.get("Name").cloned().unwrap_or_default(),
+        latest_version: info.get("Version").cloned(),
+        description: info.get("Description").cloned(),
+        url: info.get("URL").cloned(),
+        licenses: get_list("Licenses"),
+        depends: get_list("Depends On"),
+        opt_depends: get_list("Optional Deps"),
+        provides: get_list("Provides"),
+        conflicts: get_list("Conflicts With"),
+        maintainer: info.get("Packager").cloned(),
+        ..Default::default()
+    };
+}
+
+fn from_aur_info(info: &AurPackageInfoResult) -> PackageDetails {
+    return PackageDetails {
+        repo: "aur".to_string(),
+        name: info.name.to_owned(),
+        latest_version: Some(info.version.to_owned()),
+        description: info.description.to_owned(),
+        url: info.url.to_owned(),
+        licenses: info.license.to_owned().unwrap_or_default(),
+        depends: info.depends.to_owned().unwrap_or_default(),
+        make_depends: info.make_depends.to_owned().unwrap_or_default(),
+        opt_depends: info.opt_depends.to_owned().unwrap_or_default(),
+        provides: info.provides.to_owned().unwrap_or_default(),
+        conflicts: info.conflicts.to_owned().unwrap_or_default(),
+        maintainer: info.maintainer.to_owned(),
+        votes: Some(info.num_votes),
+        popularity: Some(info.popularity),
+        out_of_date: info.out_of_date,
+        ..Default::default()
+    };
+}
+
+/// Parses the `Key : Value` output of `pacman -Qi` or `pacman -Si`, one map per package.
+/// `None` values are left out.
+fn get_pacman_info(
+    operation: &str,
+    package: &str,
+) -> Result<Vec<HashMap<String, String>>, Box<dyn Error>> {
+    let command = Command::new("pacman")
+        .args([operation, package])
+        .env("LC_ALL", "C")
+        .output()?;
+
+    if !command.status.success() {
+        return Ok(vec![]);
+    }
+
+    let output = String::from_utf8(command.stdout)?;
+    let mut infos: Vec<HashMap<String, String>> = vec![];
+
+    for section in output.split("\n\n") {
+        let mut info: HashMap<String, String> = HashMap::new();
+        let mut last_key = String::new();
+
+        for line in section.lines() {
+            match line.split_once(" : ") {
+                Some((key, value)) if !line.starts_with(' ') => {
+                    last_key = key.trim().to_string();
+
+                    if value.trim() != "None" {
+                        info.insert(last_key.to_owned(), value.trim().to_string());
+                    }
+                }
+                // Values like the optional dependencies continue on the next lines
+                _ => {
+                    if let Some(value) = info.get_mut(&last_key) {
+                        value.push('\n');
+                        value.push_str(line.trim());
+                    }
+                }
+            }
+        }
+
+        if !info.is_empty() {
+            infos.push(info);
+        }
+    }
+
+    return Ok(infos);
+}
+
+/// Formats a unix timestamp as `YYYY-MM-DD` in UTC
+fn format_date(timestamp: usize) -> String {
+    // Converts the days since the epoch to a civil date, see https://howardhinnant.github.io/date_algorithms.html
+    let days = (timestamp / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    return format!("{year:04}-{month:02}-{day:02}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_the_epoch() {
+        assert_eq!(format_date(0), "1970-01-01");
+    }
+
+    #[test]
+    fn formats_leap_days() {
+        assert_eq!(format_date(951782400), "2000-02-29");
+        assert_eq!(format_date(951868799), "2000-02-29");
+        assert_eq!(format_date(1709164800), "2024-02-29");
+        assert_eq!(format_date(1709251200), "2024-03-01");
+    }
+
+    #[test]
+    fn formats_century_without_leap_day() {
+        assert_eq!(format_date(4107456000), "2100-02-28");
+    }
+
+    #[test]
+    fn formats_the_end_of_a_year() {
+        assert_eq!(format_date(1735689599), "2024-12-31");
+    }
+}
@@ -1,18 +1,28 @@
 use std::{
+    collections::HashMap,
     error::Error,
-    fs::{self},
-    path::Path,
+    path::{Path, PathBuf},
     process::{Command, exit},
 };
 
-use inquire::{Text, validator::Validation};
-use reqwest::get;
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    chroot::{build_in_chroot, prepare_chroot},
     config::get_config,
-    search::{SearchQuery, on_search_command, search_aur_packages},
-    utils::{run, run_hidden_in_path, run_in_path, show_message},
+    conflicts::check_conflicts,
+    dependencies::{AurBuild, check_edited_dependencies, resolve_aur_build_order},
+    git::{clear_edited_build, has_edited_build, mark_edited_build, mark_installed},
+    local_repo::add_to_local_repo,
+    pgp_keys::import_pgp_keys,
+    policy::enforce_policy,
+    providers::{get_providers, pick_provider},
+    review::prompt_review,
+    search::{SearchQuery, on_search_command},
+    shared_cache::{find_cached_packages, publish_packages},
+    trust::show_trust_summary,
+    utils::{run_checked, run_checked_in_path, run_hidden_in_path, show_message},
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -20,7 +30,7 @@ pub struct AurPackageInfoResponse {
     pub results: Vec<AurPackageInfoResult>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct AurPackageInfoResult {
     #[serde(rename = "LastModified")]
     pub last_modified: usize,
@@ -33,6 +43,65 @@ pub struct AurPackageInfoResult {
 
     #[serde(rename = "Name")]
     pub name: String,
+
+    #[serde(rename = "PackageBase")]
+    pub package_base: String,
+
+    /// Empty when the package is orphaned
+    #[serde(rename = "Maintainer")]
+    pub maintainer: Option<String>,
+
+    #[serde(rename = "NumVotes", default)]
+    pub num_votes: usize,
+
+    #[serde(rename = "Popularity", default)]
+    pub popularity: f64,
+
+    #[serde(rename = "FirstSubmitted", default)]
+    pub first_submitted: usize,
+
+    /// When the package was flagged out of date
+    #[serde(rename = "OutOfDate")]
+    pub out_of_date: Option<usize>,
+
+    #[serde(rename = "URL")]
+    pub url: Option<String>,
+
+    #[serde(rename = "License")]
+    pub license: Option<Vec<String>>,
+
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+
+    #[serde(rename = "Depends")]
+    pub depends: Option<Vec<String>>,
+
+    #[serde(rename = "MakeDepends")]
+    pub make_depends: Option<Vec<String>>,
+
+    #[serde(rename = "OptDepends")]
+    pub opt_depends: Option<Vec<String>>,
+
+    #[serde(rename = "Provides")]
+    pub provides: Option<Vec<String>>,
+
+    #[serde(rename = "Conflicts")]
+    pub conflicts: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InstallReason {
+    Dependency,
+    Explicit,
+}
+
+impl InstallReason {
+    pub fn flag(&self) -> &'static str {
+        return match self {
+            InstallReason::Dependency => "--asdeps",
+            InstallReason::Explicit => "--asexplicit",
+        };
+    }
 }
 
 pub async fn on_install_command(
@@ -40,32 +109,74 @@ pub async fn on_install_command(
     search_fallback: bool,
     review: bool,
     confirm_installation: bool,
+    chroot: bool,
+    reason: Option<InstallReason>,
 ) -> Result<(), Box<dyn Error>> {
-    let packages_len = packages.len();
+    let config = get_config()?;
+    let mut package_files: Vec<String> = vec![];
+    let mut local_dirs: Vec<PathBuf> = vec![];
+    let mut repo_packages: Vec<String> = vec![];
+    let mut unresolved_packages: Vec<(String, bool)> = vec![];
+    let mut missing_packages: Vec<String> = vec![];
+
+    // A package can exist on multiple repos like on cachyos, so <repo>/<package> forces the source.
+    // aur/<package> skips the repositories entirely.
+    for package in &packages {
+        let path = Path::new(package);
+
+        if is_package_file(path) {
+            package_files.push(package.to_owned());
+            continue;
+        }
+
+        if is_package_build_dir(package) {
+            local_dirs.push(path.to_path_buf());
+            continue;
+        }
 
-    if packages_len == 1 {
-        let package = packages.get(0).unwrap();
-        let command = Command::new("pacman").args(["-Si", package]).output()?;
-        let found = command.status.success();
+        match package.split_once('/') {
+            Some(("aur", name)) => unresolved_packages.push((name.to_owned(), true)),
+            Some(_) if is_repo_package(package)? => repo_packages.push(package.to_owned()),
+            Some(_) => missing_packages.push(package.to_owned()),
+            None => {
+                let repos = get_package_repos(package)?;
 
-        if found {
-            install_packages(vec![package.to_string()], confirm_installation)?;
-            exit(0);
+                match config.preferred_repo(&repos) {
+                    Some(repo) => repo_packages.push(format!("{repo}/{package}")),
+                    None => unresolved_packages.push((package.to_owned(), false)),
+                }
+            }
         }
+    }
 
-        let aur_packages = search_aur_packages(package).await?;
+    let unresolved_names: Vec<&String> = unresolved_packages.iter().map(|(name, _)| name).collect();
+    let aur_infos = get_aur_packages_info(&unresolved_names).await?;
+    let mut aur_packages: Vec<String> = vec![];
 
-        let found = aur_packages
-            .iter()
-            .any(|aur_package| &aur_package.package == package);
+    for (package, aur_only) in unresolved_packages {
+        if aur_infos.iter().any(|info| info.name == package) {
+            aur_packages.push(package);
+            continue;
+        }
 
-        if found {
-            install_aur_package(package, review).await?;
-            exit(0);
+        // Virtual packages like java-runtime are resolved to the package that provides them
+        let providers = get_providers(&package, !aur_only).await?;
+
+        match pick_provider(&package, providers)? {
+            Some(provider) if provider.is_aur() => aur_packages.push(provider.package),
+            Some(provider) => repo_packages.push(format!("{}/{}", provider.repo, provider.package)),
+            None if aur_only => missing_packages.push(format!("aur/{package}")),
+            None => missing_packages.push(package),
         }
+    }
+
+    if packages.len() == 1 && missing_packages.len() == 1 {
+        let package = missing_packages.first().unwrap();
 
         if search_fallback {
-            on_search_command(package.to_owned(), true, Some(review)).await?;
+            let (_, name) = package.split_once('/').unwrap_or(("", package));
+
+            on_search_command(name.to_owned(), None, true, Some(review), Some(chroot)).await?;
             exit(0);
         }
 
@@ -73,159 +184,478 @@ pub async fn on_install_command(
         exit(1);
     }
 
-    // This should be implemented in case a package exist on multiple repos like on cachyos
-    // pacman -S <repo_name>/<package_name>
+    if !missing_packages.is_empty() {
+        show_message(format!(
+            "Packages not found: {}",
+            missing_packages.join(", ")
+        ));
+    }
+
+    if repo_packages.is_empty()
+        && aur_packages.is_empty()
+        && package_files.is_empty()
+        && local_dirs.is_empty()
+    {
+        exit(1);
+    }
+
+    if !package_files.is_empty() {
+        install_package_files(package_files, confirm_installation, reason)?;
+    }
+
+    if !repo_packages.is_empty() {
+        show_message(format!("Installing {}", repo_packages.join(", ")));
+        install_packages(repo_packages, confirm_installation, reason)?;
+    }
+
+    if !aur_packages.is_empty() || !local_dirs.is_empty() {
+        install_package_builds(
+            &aur_packages,
+            &[],
+            &local_dirs,
+            review,
+            chroot,
+            reason,
+            &HashMap::new(),
+        )
+        .await?;
+    }
 
     Ok(())
 }
 
+pub fn is_repo_package(package: &str) -> Result<bool, Box<dyn Error>> {
+    let command = Command::new("pacman").args(["-Si", package]).output()?;
+
+    return Ok(command.status.success());
+}
+
+/// Every repository that has the package, in pacman.conf order
+pub fn get_package_repos(package: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let command = Command::new("pacman")
+        .args(["-Si", package])
+        .env("LC_ALL", "C")
+        .output()?;
+
+    if !command.status.success() {
+        return Ok(vec![]);
+    }
+
+    let output = String::from_utf8(command.stdout)?;
+
+    let repos: Vec<String> = output
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+
+            if key.trim() == "Repository" {
+                Some(value.trim().to_string())
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    return Ok(repos);
+}
+
+pub async fn get_aur_packages_info<S: AsRef<str>>(
+    packages: &[S],
+) -> Result<Vec<AurPackageInfoResult>, Box<dyn Error>> {
+    if packages.is_empty() {
+        return Ok(vec![]);
+    }
+
+    // Names like libc++ have to be encoded, a plain + would be read as a space
+    let mut params = vec![("v", "5"), ("type", "info")];
+    params.extend(packages.iter().map(|package| ("arg[]", package.as_ref())));
+
+    let url = Url::parse_with_params("https://aur.archlinux.org/rpc/", &params)?;
+
+    let response_json = reqwest::get(url).await?.text().await?;
+    let response: AurPackageInfoResponse = serde_json::from_str(&response_json)?;
+
+    return Ok(response.results);
+}
+
 pub fn install_packages(
     packages: Vec<String>,
     confirm_installation: bool,
+    reason: Option<InstallReason>,
 ) -> Result<(), Box<dyn Error>> {
     let mut command = vec!["sudo".to_string(), "pacman".to_string(), "-S".to_string()];
     let mut packages = packages;
 
     command.append(&mut packages);
 
+    if let Some(reason) = reason {
+        command.push(reason.flag().to_string());
+    }
+
+    if !confirm_installation {
+        command.push("--noconfirm".to_string());
+    }
+
+    run_checked(&command)?;
+
+    Ok(())
+}
+
+pub fn install_package_files(
+    files: Vec<String>,
+    confirm_installation: bool,
+    reason: Option<InstallReason>,
+) -> Result<(), Box<dyn Error>> {
+    let mut command = vec!["sudo".to_string(), "pacman".to_string(), "-U".to_string()];
+    let mut files = files;
+
+    command.append(&mut files);
+
+    if let Some(reason) = reason {
+        command.push(reason.flag().to_string());
+    }
+
     if !confirm_installation {
         command.push("--noconfirm".to_string());
     }
 
-    run(&command)?;
+    run_checked(&command)?;
 
     Ok(())
 }
 
+/// If the argument is a local package file like `./foo-1.0-1-x86_64.pkg.tar.zst`.
+/// Signatures next to the package file aren't packages.
+fn is_package_file(path: &Path) -> bool {
+    return path.is_file()
+        && path.extension().is_none_or(|extension| extension != "sig")
+        && get_package_file_name(path).is_some();
+}
+
+/// If the argument is a directory with a PKGBUILD like `./foo/` or `.`
+pub fn is_package_build_dir(package: &str) -> bool {
+    return Path::new(package).join("PKGBUILD").is_file();
+}
+
 pub fn install_from_query(query: &SearchQuery) -> Result<(), Box<dyn Error>> {
     install_packages(
-        vec![query.package.to_string()],
+        vec![format!("{}/{}", query.repo, query.package)],
         get_config()?.confirm_installation,
+        None,
     )?;
 
     Ok(())
 }
 
-pub async fn install_aur_package(package: &str, review: bool) -> Result<(), Box<dyn Error>> {
-    let url = format!("https://aur.archlinux.org/rpc/?v=5&type=info&arg={package}");
+pub async fn install_aur_package(
+    package: &str,
+    review: bool,
+    chroot: bool,
+) -> Result<(), Box<dyn Error>> {
+    return install_aur_packages(&[package], review, chroot).await;
+}
 
-    let response_json = reqwest::get(url).await?.text().await?;
-    let response: AurPackageInfoResponse = serde_json::from_str(&response_json)?;
+pub async fn install_aur_packages<S: AsRef<str>>(
+    packages: &[S],
+    review: bool,
+    chroot: bool,
+) -> Result<(), Box<dyn Error>> {
+    return install_package_builds(packages, &[], &[], review, chroot, None, &HashMap::new()).await;
+}
 
-    let info = response
-        .results
-        .get(0)
-        .ok_or_else(|| "Failed to get package info".to_string())?;
-
-    let cache_dir = dirs::cache_dir()
-        .expect("Failed to get cache dir")
-        .join("aurcat")
-        .join(&package)
-        .join(format!(
-            "{}-{}",
-            &info.last_modified.to_string(),
-            &info.version
-        ));
+/// Reviews, builds and installs the AUR packages and local package build directories with their AUR dependencies.
+/// The reason applies to the requested packages, AUR dependencies that aren't installed yet become dependencies.
+/// The extra dependencies are built as dependencies even if no requested package depends on them.
+pub async fn install_package_builds<S: AsRef<str>>(
+    packages: &[S],
+    dependencies: &[String],
+    local_dirs: &[PathBuf],
+    review: bool,
+    chroot: bool,
+    reason: Option<InstallReason>,
+    locked_commits: &HashMap<String, String>,
+) -> Result<(), Box<dyn Error>> {
+    let mut names: Vec<&str> = packages.iter().map(|package| package.as_ref()).collect();
+    names.extend(dependencies.iter().map(|dependency| dependency.as_str()));
 
-    if !cache_dir.exists() {
-        show_message("Downloading Package Build");
+    let mut builds = resolve_aur_build_order(&names, local_dirs, chroot, locked_commits).await?;
 
-        fs::create_dir_all(&cache_dir).expect("Failed to create cache dir");
+    enforce_policy(&builds)?;
 
-        let tgz_path = cache_dir.clone().join("content.tar.gz");
+    show_aur_dependencies(&builds, packages);
 
-        let bytes = get(&format!("https://aur.archlinux.org{}", &info.url_path))
-            .await?
-            .bytes()
-            .await?;
+    if !check_conflicts(&builds)? {
+        return Ok(());
+    }
 
-        fs::write(&tgz_path, &bytes)?;
+    if !review_builds(&mut builds, review, chroot)? {
+        return Ok(());
+    }
 
-        run_hidden_in_path(
-            &[
-                "tar",
-                "-xzf",
-                &tgz_path.display().to_string(),
-                "--strip-components=1",
-            ],
-            &cache_dir,
-        )
-        .expect("Failed to untar aur file");
-
-        if review {
-            let result = Text::new(&format!(
-                "Would you like to review? {}\n",
-                if review { "(Y/n)" } else { "(y/N)" }
-            ))
-            .with_validator(move |input: &str| {
-                let lower_answer = input.to_lowercase().to_string();
-
-                match lower_answer.as_str() {
-                    "y" => Ok(Validation::Valid),
-                    "yes" => Ok(Validation::Valid),
-                    "n" => Ok(Validation::Valid),
-                    "no" => Ok(Validation::Valid),
-                    _ => Ok(Validation::Invalid("Invalid Answer".into())),
-                }
-            })
-            .with_default(if review { "Y" } else { "N" })
-            .prompt();
+    let chroot_dir = if chroot {
+        Some(prepare_chroot()?)
+    } else {
+        None
+    };
 
-            let answer = result?.to_lowercase();
+    let mut built_packages: HashMap<String, Vec<PathBuf>> = HashMap::new();
 
-            match answer.as_str() {
-                "y" => {
-                    let install = review_package(&cache_dir)?;
+    for build in &builds {
+        let injected_packages = get_injected_packages(build, &builds, &built_packages);
+        let package_files = build_aur_package(build, chroot_dir.as_deref(), &injected_packages)?;
 
-                    if install {
-                        run_in_path(&["makepkg", "-si"], &cache_dir)?;
-                    } else {
-                        exit(0);
-                    }
-                }
-                "yes" => {
-                    let install = review_package(&cache_dir)?;
-
-                    if install {
-                        run_in_path(&["makepkg", "-si"], &cache_dir)?;
-                    } else {
-                        exit(0);
-                    }
-                }
-                _ => {}
-            };
+        built_packages.insert(build.info.package_base.to_owned(), package_files.clone());
+
+        let reason = if is_requested(build, packages) {
+            reason
+        } else {
+            Some(InstallReason::Dependency)
+        };
+
+        install_built_packages(build, &package_files, reason)?;
+    }
+
+    Ok(())
+}
+
+pub fn show_aur_dependencies<S: AsRef<str>>(builds: &[AurBuild], packages: &[S]) {
+    let dependencies: Vec<&str> = builds
+        .iter()
+        .filter(|build| !is_requested(build, packages))
+        .flat_map(|build| build.pkgnames.iter())
+        .map(|name| name.as_str())
+        .collect();
+
+    if !dependencies.is_empty() {
+        show_message(format!(
+            "Building AUR dependencies: {}",
+            dependencies.join(", ")
+        ));
+    }
+}
+
+/// If the build is a local directory or one of the packages asked for, instead of a dependency
+pub fn is_requested<S: AsRef<str>>(build: &AurBuild, packages: &[S]) -> bool {
+    return build.local
+        || build
+            .pkgnames
+            .iter()
+            .any(|name| packages.iter().any(|package| package.as_ref() == name));
+}
+
+/// Shows the trust summary, reviews the changed builds and imports their PGP keys.
+/// Returns false if the user cancels.
+pub fn review_builds(
+    builds: &mut [AurBuild],
+    review: bool,
+    chroot: bool,
+) -> Result<bool, Box<dyn Error>> {
+    show_trust_summary(builds)?;
+
+    for build in builds.iter_mut() {
+        if build.changed && !prompt_review(build, review)? {
+            return Ok(false);
         }
     }
 
-    run_in_path(&["makepkg", "-si"], &cache_dir)?;
+    check_edited_dependencies(builds, chroot)?;
 
-    Ok(())
+    return import_pgp_keys(builds);
+}
+
+pub fn install_built_packages(
+    build: &AurBuild,
+    package_files: &[PathBuf],
+    reason: Option<InstallReason>,
+) -> Result<(), Box<dyn Error>> {
+    let config = get_config()?;
+
+    // Installed packages keep their install reason, only the new ones become dependencies.
+    // Otherwise rebuilding an explicitly installed package for a chroot would turn it into an orphan.
+    let new_dependencies: Vec<String> = match reason {
+        Some(InstallReason::Dependency) => package_files
+            .iter()
+            .filter_map(get_package_file_name)
+            .filter(|name| !is_installed(name))
+            .collect(),
+        _ => vec![],
+    };
+
+    let reason = reason.filter(|reason| reason != &InstallReason::Dependency);
+
+    // With a local repository the packages are added to it and installed from there.
+    // Refreshing the sync databases without upgrading would be a partial upgrade, so pacman -U is used.
+    let package_files = match config.local_repo_dir() {
+        Some(_) => add_to_local_repo(&config, package_files)?,
+        None => package_files.to_vec(),
+    };
+
+    let mut command = vec!["sudo".to_string(), "pacman".to_string(), "-U".to_string()];
+    command.extend(package_files.iter().map(|file| file.display().to_string()));
+
+    if let Some(reason) = reason {
+        command.push(reason.flag().to_string());
+    }
+
+    run_checked(&command)?;
+
+    if !new_dependencies.is_empty() {
+        let mut command = vec![
+            "sudo".to_string(),
+            "pacman".to_string(),
+            "-D".to_string(),
+            InstallReason::Dependency.flag().to_string(),
+        ];
+        command.extend(new_dependencies);
+
+        run_checked(&command)?;
+    }
+
+    // Only a successful install moves the installed ref, otherwise the next run would skip the review
+    if !build.local {
+        mark_installed(&build.path)?;
+    }
+
+    return Ok(());
+}
+
+fn is_installed(package: &str) -> bool {
+    return Command::new("pacman")
+        .args(["-Qq", package])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
 }
 
-fn review_package<P: AsRef<Path>>(cache_dir: P) -> Result<bool, Box<dyn Error>> {
-    let pkgbuild_path = cache_dir.as_ref().join("PKGBUILD");
-    run(&["bat", &pkgbuild_path.display().to_string()])?;
+/// The already built packages of every AUR dependency of the build, including the indirect ones
+pub fn get_injected_packages(
+    build: &AurBuild,
+    builds: &[AurBuild],
+    built_packages: &HashMap<String, Vec<PathBuf>>,
+) -> Vec<PathBuf> {
+    let mut pkgbases: Vec<&String> = build.dependencies.iter().collect();
+    let mut index = 0;
+
+    while let Some(pkgbase) = pkgbases.get(index) {
+        let dependencies = builds
+            .iter()
+            .find(|build| &&build.info.package_base == pkgbase)
+            .map(|build| &build.dependencies);
+
+        for dependency in dependencies.into_iter().flatten() {
+            if !pkgbases.contains(&dependency) {
+                pkgbases.push(dependency);
+            }
+        }
+
+        index += 1;
+    }
+
+    return pkgbases
+        .iter()
+        .filter_map(|pkgbase| built_packages.get(*pkgbase))
+        .flatten()
+        .cloned()
+        .collect();
+}
+
+/// Builds the package base once and returns the package files of the requested split packages.
+/// With a chroot dir the package is built in a clean chroot with the injected packages installed.
+/// Packages in the shared cache are used instead of building and new builds are published to it.
+pub fn build_aur_package(
+    build: &AurBuild,
+    chroot_dir: Option<&Path>,
+    injected_packages: &[PathBuf],
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let package_files = get_package_files(&build.path)?;
+
+    // Edited builds are always rebuilt and never come from the shared cache.
+    // Package files left from an earlier edited build don't match the upstream snapshot anymore.
+    let has_edited_files = !build.local && has_edited_build(&build.path);
+    let rebuild = build.edited || has_edited_files;
+    let mut built = false;
+
+    if rebuild || package_files.is_empty() || package_files.iter().any(|file| !file.exists()) {
+        if !build.edited
+            && let Some(cached_files) = find_cached_packages(build)?
+        {
+            show_message(format!(
+                "Using {} from the Shared Cache",
+                &build.info.package_base
+            ));
+
+            return Ok(cached_files);
+        }
+
+        show_message(format!("Building {}", &build.info.package_base));
+
+        match chroot_dir {
+            Some(chroot_dir) => build_in_chroot(chroot_dir, &build.path, injected_packages)?,
+            None => {
+                let mut command = vec!["makepkg", "-s"];
 
-    let result = Text::new(&format!("Would you like to install the package? (Y/n)\n"))
-        .with_validator(move |input: &str| {
-            let lower_answer = input.to_lowercase().to_string();
+                if rebuild {
+                    command.push("-f");
+                }
 
-            match lower_answer.as_str() {
-                "y" => Ok(Validation::Valid),
-                "yes" => Ok(Validation::Valid),
-                "n" => Ok(Validation::Valid),
-                "no" => Ok(Validation::Valid),
-                _ => Ok(Validation::Invalid("Invalid Answer".into())),
+                run_checked_in_path(&command, &build.path)?;
             }
+        }
+
+        built = true;
+    }
+
+    if !build.local && build.edited {
+        mark_edited_build(&build.path)?;
+    } else if built && has_edited_files {
+        clear_edited_build(&build.path)?;
+    }
+
+    let debug_package = format!("{}-debug", &build.info.package_base);
+
+    // VCS packages can change their version while building so the list is fetched again
+    let package_files: Vec<PathBuf> = get_package_files(&build.path)?
+        .into_iter()
+        .filter(|file| {
+            get_package_file_name(file)
+                .is_some_and(|name| build.pkgnames.contains(&name) || name == debug_package)
         })
-        .with_default("Y")
-        .prompt();
+        .collect();
+
+    if package_files.is_empty() {
+        return Err(format!(
+            "Failed to find the built packages of {}",
+            &build.info.package_base
+        )
+        .into());
+    }
+
+    // Only files built in this run are known to come from the current snapshot
+    if built {
+        publish_packages(build, &package_files)?;
+    }
 
-    let answer = result?.to_lowercase();
+    return Ok(package_files);
+}
 
-    return match answer.as_str() {
-        "y" => Ok(true),
-        "yes" => Ok(true),
-        _ => Ok(false),
-    };
+/// The package files makepkg produces for the package build
+fn get_package_files<P: AsRef<Path>>(path: P) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let output = run_hidden_in_path(&["makepkg", "--packagelist"], path)?;
+
+    return Ok(output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| PathBuf::from(line.trim()))
+        .collect());
+}
+
+/// Gets the package name from a file like `foo-bar-1.0-1-x86_64.pkg.tar.zst`
+pub fn get_package_file_name<P: AsRef<Path>>(path: P) -> Option<String> {
+    let file_name = path.as_ref().file_name()?.to_str()?;
+    let (stem, _) = file_name.split_once(".pkg.tar")?;
+
+    // Name, version, release and architecture
+    let parts: Vec<&str> = stem.rsplitn(4, '-').collect();
+
+    return parts.get(3).map(|name| name.to_string());
 }
@@ -2,7 +2,7 @@ use std::error::Error;
 
 use sniffer_rs::sniffer::Sniffer;
 
-use crate::utils::run_hidden;
+use crate::{config::get_config, local_repo::get_local_repo_packages, utils::run_hidden};
 
 #[derive(Debug)]
 pub struct PackageQuery {
@@ -24,10 +24,7 @@ pub fn on_list_command(aur: bool, filter: Option<String>) -> Result<(), Box<dyn
     };
 
     if let Some(filter) = filter {
-        packages = packages
-            .into_iter()
-            .filter(|query| sniffer.matches(&query.package, &filter))
-            .collect();
+        packages.retain(|query| sniffer.matches(&query.package, &filter));
     }
 
     for query in packages {
@@ -47,7 +44,6 @@ pub fn get_repo_packages() -> Result<Vec<PackageQuery>, Box<dyn Error>> {
 
     let packages: Vec<PackageQuery> = packages_output
         .lines()
-        .into_iter()
         .filter_map(|query| {
             let mut splitted = query.split_whitespace();
 
@@ -68,9 +64,8 @@ pub fn get_repo_packages() -> Result<Vec<PackageQuery>, Box<dyn Error>> {
 pub fn get_aur_packages(filter_debug: bool) -> Result<Vec<PackageQuery>, Box<dyn Error>> {
     let packages_output = run_hidden(&["pacman", "-Qm"])?;
 
-    let packages: Vec<PackageQuery> = packages_output
+    let mut packages: Vec<PackageQuery> = packages_output
         .lines()
-        .into_iter()
         .filter_map(|query| {
             let mut splitted = query.split_whitespace();
 
@@ -89,12 +84,26 @@ pub fn get_aur_packages(filter_debug: bool) -> Result<Vec<PackageQuery>, Box<dyn
         })
         .collect();
 
+    // Packages from the local repository aren't foreign anymore but still come from the AUR
+    let local_repo_packages = get_local_repo_packages(&get_config()?)?;
+
+    packages.extend(
+        local_repo_packages
+            .into_iter()
+            .filter(|query| !filter_debug || !query.package.ends_with("-debug")),
+    );
+
     return Ok(packages);
 }
 
 pub fn get_all_packages() -> Result<Vec<PackageQuery>, Box<dyn Error>> {
-    let mut pkgs = get_repo_packages()?;
     let mut aur_pkgs = get_aur_packages(true)?;
+
+    let mut pkgs: Vec<PackageQuery> = get_repo_packages()?
+        .into_iter()
+        .filter(|query| !aur_pkgs.iter().any(|aur| aur.package == query.package))
+        .collect();
+
     pkgs.append(&mut aur_pkgs);
 
     return Ok(pkgs);
@@ -0,0 +1,124 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{
+    config::{Config, get_config},
+    list::PackageQuery,
+    utils::{run_checked, show_message},
+};
+
+pub fn on_repo_init_command() -> Result<(), Box<dyn Error>> {
+    let config = get_config()?;
+
+    let repo_dir = config
+        .local_repo_dir()
+        .ok_or_else(|| "Set local_repo_dir in the config first".to_string())?;
+
+    fs::create_dir_all(&repo_dir)?;
+
+    let repo_dir = repo_dir.canonicalize()?;
+    let db_path = get_db_path(&config, &repo_dir);
+
+    if !db_path.exists() {
+        run_checked(&["repo-add", &db_path.display().to_string()])?;
+    }
+
+    show_message("Add this to /etc/pacman.conf and run sudo pacman -Sy:");
+
+    println!(
+        "\n[{}]\nSigLevel = Optional TrustAll\nServer = file://{}\n",
+        &config.local_repo_name,
+        repo_dir.display()
+    );
+
+    Ok(())
+}
+
+/// Copies the package files to the local repository and adds them to its database.
+/// Returns the package files inside the repository.
+pub fn add_to_local_repo(
+    config: &Config,
+    package_files: &[PathBuf],
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let repo_dir = config
+        .local_repo_dir()
+        .ok_or_else(|| "Set local_repo_dir in the config first".to_string())?;
+
+    fs::create_dir_all(&repo_dir)?;
+
+    let repo_dir = repo_dir.canonicalize()?;
+    let mut command = vec![
+        "repo-add".to_string(),
+        "--remove".to_string(),
+        get_db_path(config, &repo_dir).display().to_string(),
+    ];
+
+    let mut repo_files: Vec<PathBuf> = vec![];
+
+    for file in package_files {
+        let file_name = file
+            .file_name()
+            .ok_or_else(|| format!("Invalid package file {}", file.display()))?;
+
+        let repo_file = repo_dir.join(file_name);
+
+        if file.canonicalize()? != repo_file {
+            fs::copy(file, &repo_file)?;
+        }
+
+        command.push(repo_file.display().to_string());
+        repo_files.push(repo_file);
+    }
+
+    run_checked(&command)?;
+
+    return Ok(repo_files);
+}
+
+/// Installed packages that come from the local repository
+pub fn get_local_repo_packages(config: &Config) -> Result<Vec<PackageQuery>, Box<dyn Error>> {
+    if config.local_repo_dir().is_none() {
+        return Ok(vec![]);
+    }
+
+    let command = Command::new("pacman")
+        .args(["-Sl", &config.local_repo_name])
+        .env("LC_ALL", "C")
+        .output()?;
+
+    let output = String::from_utf8(command.stdout)?;
+
+    let packages: Vec<PackageQuery> = output
+        .lines()
+        .filter(|line| line.contains("[installed"))
+        .filter_map(|line| {
+            let mut splitted = line.split_whitespace();
+
+            let _repo = splitted.next()?;
+            let package = splitted.next()?.to_string();
+            let repo_version = splitted.next()?.to_string();
+
+            // The installed version is only shown when it differs like [installed: 1.0-1]
+            let version = match line.split_once("[installed: ") {
+                Some((_, rest)) => rest.trim_end_matches(']').to_string(),
+                None => repo_version,
+            };
+
+            Some(PackageQuery {
+                package,
+                version,
+                aur: true,
+            })
+        })
+        .collect();
+
+    return Ok(packages);
+}
+
+fn get_db_path(config: &Config, repo_dir: &Path) -> PathBuf {
+    return repo_dir.join(format!("{}.db.tar.gz", &config.local_repo_name));
+}
@@ -0,0 +1,191 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    git::{
+        get_commit_time, get_file_at_commit, get_file_commits, get_head_commit,
+        get_installed_commit, sync_aur_repo,
+    },
+    install::{get_aur_packages_info, install_package_builds},
+    list::get_aur_packages,
+    srcinfo::SrcInfo,
+    utils::{run_hidden, show_message},
+};
+
+pub const DEFAULT_LOCK_FILE: &str = "aurcat.lock";
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct LockFile {
+    pub packages: Vec<LockedPackage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LockedPackage {
+    pub name: String,
+    pub package_base: String,
+    pub version: String,
+
+    /// The AUR LastModified time of the locked revision
+    pub last_modified: usize,
+
+    /// The commit of the AUR repository the package was built from
+    pub commit: String,
+
+    /// If the package was installed as a dependency of another package instead of explicitly
+    #[serde(default)]
+    pub dependency: bool,
+}
+
+/// Writes the version and AUR commit of every installed AUR package to the lockfile
+pub async fn on_lock_command(output: Option<PathBuf>) -> Result<(), Box<dyn Error>> {
+    let output = output.unwrap_or_else(|| PathBuf::from(DEFAULT_LOCK_FILE));
+    let installed_packages = get_aur_packages(true)?;
+    let dependency_packages = get_dependency_packages()?;
+
+    let names: Vec<&String> = installed_packages
+        .iter()
+        .map(|query| &query.package)
+        .collect();
+
+    let infos = get_aur_packages_info(&names).await?;
+    let mut lock_file = LockFile::default();
+    let mut skipped_packages: Vec<String> = vec![];
+
+    for query in &installed_packages {
+        let Some(info) = infos.iter().find(|info| info.name == query.package) else {
+            skipped_packages.push(format!("{} (not in the AUR)", &query.package));
+            continue;
+        };
+
+        let path = sync_aur_repo(&info.package_base)?;
+
+        let Some(commit) = find_version_commit(&path, &query.version)? else {
+            skipped_packages.push(format!(
+                "{} (no AUR commit has version {})",
+                &query.package, &query.version
+            ));
+            continue;
+        };
+
+        // The AUR only knows the LastModified time of the latest revision
+        let last_modified = if commit == get_head_commit(&path)? {
+            info.last_modified
+        } else {
+            get_commit_time(&path, &commit)?
+        };
+
+        lock_file.packages.push(LockedPackage {
+            name: query.package.to_owned(),
+            package_base: info.package_base.to_owned(),
+            version: query.version.to_owned(),
+            last_modified,
+            commit,
+            dependency: dependency_packages.contains(&query.package),
+        });
+    }
+
+    lock_file.packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+    fs::write(&output, toml::to_string_pretty(&lock_file)?)?;
+
+    if !skipped_packages.is_empty() {
+        show_message(format!("Skipped packages: {}", skipped_packages.join(", ")));
+    }
+
+    show_message(format!(
+        "Locked {} packages in {}",
+        lock_file.packages.len(),
+        output.display()
+    ));
+
+    Ok(())
+}
+
+/// Builds and installs the exact AUR revisions of the lockfile
+pub async fn on_locked_install_command(
+    path: &Path,
+    review: bool,
+    chroot: bool,
+) -> Result<(), Box<dyn Error>> {
+    let lock_file = read_lock_file(path)?;
+
+    if lock_file.packages.is_empty() {
+        show_message("The lockfile has no packages");
+        return Ok(());
+    }
+
+    // Only the explicitly installed packages are requested, the rest is installed as dependencies
+    let (dependencies, packages): (Vec<&LockedPackage>, Vec<&LockedPackage>) = lock_file
+        .packages
+        .iter()
+        .partition(|package| package.dependency);
+
+    let packages: Vec<String> = packages
+        .iter()
+        .map(|package| package.name.to_owned())
+        .collect();
+
+    let dependencies: Vec<String> = dependencies
+        .iter()
+        .map(|package| package.name.to_owned())
+        .collect();
+
+    let locked_commits: HashMap<String, String> = lock_file
+        .packages
+        .iter()
+        .map(|package| (package.package_base.to_owned(), package.commit.to_owned()))
+        .collect();
+
+    return install_package_builds(
+        &packages,
+        &dependencies,
+        &[],
+        review,
+        chroot,
+        None,
+        &locked_commits,
+    )
+    .await;
+}
+
+pub fn read_lock_file(path: &Path) -> Result<LockFile, Box<dyn Error>> {
+    let content = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+
+    return Ok(toml::from_str(&content)?);
+}
+
+/// Installed packages with the dependency install reason
+fn get_dependency_packages() -> Result<Vec<String>, Box<dyn Error>> {
+    let output = run_hidden(&["pacman", "-Qqd"])?;
+
+    return Ok(output.lines().map(|line| line.trim().to_string()).collect());
+}
+
+/// The commit that built the installed version.
+/// The installed commit is preferred, otherwise the history of the .SRCINFO is searched.
+fn find_version_commit(path: &Path, version: &str) -> Result<Option<String>, Box<dyn Error>> {
+    let mut commits = get_file_commits(path, ".SRCINFO")?;
+
+    if let Some(installed_commit) = get_installed_commit(path) {
+        commits.insert(0, installed_commit);
+    }
+
+    for commit in commits {
+        let Ok(content) = get_file_at_commit(path, &commit, ".SRCINFO") else {
+            continue;
+        };
+
+        if SrcInfo::parse(&content).version() == version {
+            return Ok(Some(commit));
+        }
+    }
+
+    return Ok(None);
+}
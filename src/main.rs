@@ -1,13 +1,21 @@
+#![allow(clippy::needless_return)]
+
 use std::{env, error::Error};
 
 use clap::{Parser, error::ErrorKind};
 
 use crate::{
+    approvals::{on_reviews_list_command, on_reviews_revoke_command},
+    build::on_build_command,
     clear_cache::on_clear_cache_command,
-    cli::{Cli, MainCommand},
+    cli::{Cli, MainCommand, RepoCommand, ReviewsCommand},
     config::get_config,
-    install::on_install_command,
+    info::on_info_command,
+    install::{InstallReason, on_install_command},
     list::on_list_command,
+    local_repo::on_repo_init_command,
+    lock::{on_lock_command, on_locked_install_command},
+    mark::on_mark_command,
     remove_lock::on_remove_lock_command,
     search::on_search_command,
     uninstall::on_uninstall_command,
@@ -16,13 +24,31 @@ use crate::{
     utils::{run, show_message},
 };
 
+pub mod analyzer;
+pub mod approvals;
+pub mod build;
+pub mod chroot;
 pub mod clear_cache;
 pub mod cli;
 pub mod config;
+pub mod conflicts;
+pub mod dependencies;
+pub mod git;
+pub mod info;
 pub mod install;
 pub mod list;
+pub mod local_repo;
+pub mod lock;
+pub mod mark;
+pub mod pgp_keys;
+pub mod policy;
+pub mod providers;
 pub mod remove_lock;
+pub mod review;
 pub mod search;
+pub mod shared_cache;
+pub mod srcinfo;
+pub mod trust;
 pub mod uninstall;
 pub mod update;
 pub mod update_keys;
@@ -44,6 +70,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
                         confirm,
                         noreview: skip_review,
                         review,
+                        nochroot,
+                        chroot,
+                        asdeps,
+                        asexplicit,
+                        locked,
                         packages,
                     } => {
                         let search_fallback = match (skip_search, search) {
@@ -67,8 +98,58 @@ async fn main() -> Result<(), Box<dyn Error>> {
                             _ => panic!("UUH?"),
                         };
 
-                        on_install_command(packages, search_fallback, review, confirm_installation)
-                            .await?;
+                        let chroot = match (nochroot, chroot) {
+                            (true, false) => false,
+                            (false, true) => true,
+                            (false, false) => config.chroot,
+                            _ => panic!("UUH?"),
+                        };
+
+                        let reason = match (asdeps, asexplicit) {
+                            (true, false) => Some(InstallReason::Dependency),
+                            (false, true) => Some(InstallReason::Explicit),
+                            (false, false) => None,
+                            _ => panic!("UUH?"),
+                        };
+
+                        if let Some(locked) = locked {
+                            on_locked_install_command(&locked, review, chroot).await?;
+                            return Ok(());
+                        }
+
+                        on_install_command(
+                            packages,
+                            search_fallback,
+                            review,
+                            confirm_installation,
+                            chroot,
+                            reason,
+                        )
+                        .await?;
+                    }
+                    MainCommand::Build {
+                        output_dir,
+                        noreview,
+                        review,
+                        nochroot,
+                        chroot,
+                        packages,
+                    } => {
+                        let review = match (noreview, review) {
+                            (true, false) => false,
+                            (false, true) => true,
+                            (false, false) => config.aur_review,
+                            _ => panic!("UUH?"),
+                        };
+
+                        let chroot = match (nochroot, chroot) {
+                            (true, false) => false,
+                            (false, true) => true,
+                            (false, false) => config.chroot,
+                            _ => panic!("UUH?"),
+                        };
+
+                        on_build_command(packages, output_dir, review, chroot).await?;
                     }
                     MainCommand::Uninstall {
                         noconfirm,
@@ -91,6 +172,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
                         review,
                         noconfirm,
                         confirm,
+                        nochroot,
+                        chroot,
                     } => {
                         let aur = match (noaur, aur) {
                             (true, false) => false,
@@ -113,12 +196,41 @@ async fn main() -> Result<(), Box<dyn Error>> {
                             _ => panic!("UUH?"),
                         };
 
-                        on_update_command(aur, review, confirm).await?;
+                        let chroot = match (nochroot, chroot) {
+                            (true, false) => false,
+                            (false, true) => true,
+                            (false, false) => config.chroot,
+                            _ => panic!("UUH?"),
+                        };
+
+                        on_update_command(aur, review, confirm, chroot).await?;
                     }
-                    MainCommand::Search { package } => {
-                        on_search_command(package, true, None).await?
+                    MainCommand::Search { by, package } => {
+                        on_search_command(package, by, true, None, None).await?
                     }
+                    MainCommand::Info { packages } => on_info_command(packages).await?,
                     MainCommand::List { aur, filter } => on_list_command(aur, filter)?,
+                    MainCommand::Mark {
+                        asdeps,
+                        asexplicit,
+                        packages,
+                    } => {
+                        let reason = match (asdeps, asexplicit) {
+                            (true, false) => InstallReason::Dependency,
+                            (false, true) => InstallReason::Explicit,
+                            _ => panic!("UUH?"),
+                        };
+
+                        on_mark_command(packages, reason)?;
+                    }
+                    MainCommand::Lock { output } => on_lock_command(output).await?,
+                    MainCommand::Repo { command } => match command {
+                        RepoCommand::Init {} => on_repo_init_command()?,
+                    },
+                    MainCommand::Reviews { command } => match command {
+                        ReviewsCommand::List {} => on_reviews_list_command()?,
+                        ReviewsCommand::Revoke { packages } => on_reviews_revoke_command(packages)?,
+                    },
                     MainCommand::UpdateKeys {} => on_update_keys_command()?,
                     MainCommand::RemoveLock {} => on_remove_lock_command()?,
                     MainCommand::ClearCache { versions } => {
@@ -140,23 +252,29 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 .filter_map(|(index, arg)| if index > 0 { Some(arg) } else { None })
                 .collect();
 
-            if args.len() >= 1 {
-                let commands = vec![
+            if !args.is_empty() {
+                let commands = [
                     "install",
+                    "build",
                     "uninstall",
                     "update",
                     "search",
+                    "info",
                     "list",
+                    "mark",
+                    "lock",
+                    "repo",
+                    "reviews",
                     "update-keys",
                     "remove-lock",
                     "clear-cache",
                 ];
 
-                let main_command = args.get(0).unwrap();
+                let main_command = args.first().unwrap();
 
                 if e.kind() == ErrorKind::UnknownArgument
                     || e.kind() == ErrorKind::InvalidSubcommand
-                        && !commands.iter().any(|c| c == &main_command)
+                        && !commands.iter().any(|c| c == main_command)
                 {
                     match config.pacman_fallback {
                         true => {
@@ -173,6 +291,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
                                 config.search_fallback,
                                 config.aur_review,
                                 config.confirm_installation,
+                                config.chroot,
+                                None,
                             )
                             .await?;
                         }
@@ -0,0 +1,19 @@
+use std::error::Error;
+
+use crate::{install::InstallReason, utils::run_checked};
+
+pub fn on_mark_command(packages: Vec<String>, reason: InstallReason) -> Result<(), Box<dyn Error>> {
+    let mut command = vec![
+        "sudo".to_string(),
+        "pacman".to_string(),
+        "-D".to_string(),
+        reason.flag().to_string(),
+    ];
+
+    let mut packages = packages;
+    command.append(&mut packages);
+
+    run_checked(&command)?;
+
+    Ok(())
+}
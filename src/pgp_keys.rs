@@ -0,0 +1,58 @@
+use std::{error::Error, process::Command};
+
+use colored::Colorize;
+
+use crate::{
+    dependencies::AurBuild,
+    utils::{prompt_confirm, run_checked, show_message},
+};
+
+/// Checks the `validpgpkeys` of the builds and offers to receive the missing ones.
+/// Returns false if the user cancels.
+pub fn import_pgp_keys(builds: &[AurBuild]) -> Result<bool, Box<dyn Error>> {
+    let mut missing_keys: Vec<(String, String)> = vec![];
+
+    for build in builds {
+        for key in build.srcinfo.values("validpgpkeys") {
+            if missing_keys
+                .iter()
+                .any(|(missing_key, _)| missing_key == &key)
+            {
+                continue;
+            }
+
+            if !has_pgp_key(&key) {
+                missing_keys.push((key, build.info.package_base.to_owned()));
+            }
+        }
+    }
+
+    if missing_keys.is_empty() {
+        return Ok(true);
+    }
+
+    show_message("Missing PGP keys:");
+
+    for (key, package) in &missing_keys {
+        println!("{} ({})", key.bold(), package);
+    }
+
+    if !prompt_confirm("Would you like to import them?", true)? {
+        return Ok(false);
+    }
+
+    let mut command = vec!["gpg".to_string(), "--recv-keys".to_string()];
+    command.extend(missing_keys.into_iter().map(|(key, _)| key));
+
+    run_checked(&command)?;
+
+    return Ok(true);
+}
+
+fn has_pgp_key(key: &str) -> bool {
+    return Command::new("gpg")
+        .args(["--list-keys", key])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+}
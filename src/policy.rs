@@ -0,0 +1,373 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    fs,
+    path::PathBuf,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{dependencies::AurBuild, install::AurPackageInfoResult, utils::show_message};
+
+const SYSTEM_POLICY_PATH: &str = "/etc/aurcat/policy.toml";
+
+/// Allowed and denied AUR packages. Names, maintainers and licenses support `*` and `?` globs.
+/// Deny rules always win. A non empty allow list blocks everything it doesn't match.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Policy {
+    pub allow: PolicyRules,
+    pub deny: PolicyRules,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct PolicyRules {
+    pub packages: Vec<String>,
+    pub maintainers: Vec<String>,
+    pub licenses: Vec<String>,
+}
+
+/// What is known about an AUR package. Search results don't have the licenses.
+pub struct PolicySubject<'a> {
+    pub name: &'a str,
+    pub maintainer: Option<&'a str>,
+    pub licenses: Option<&'a [String]>,
+}
+
+impl<'a> From<&'a AurPackageInfoResult> for PolicySubject<'a> {
+    fn from(info: &'a AurPackageInfoResult) -> Self {
+        return PolicySubject {
+            name: &info.name,
+            maintainer: info.maintainer.as_deref(),
+            licenses: Some(info.license.as_deref().unwrap_or_default()),
+        };
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub package: String,
+    pub rule: String,
+    pub path: PathBuf,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is blocked by {} in {}",
+            self.package,
+            self.rule,
+            self.path.display()
+        )
+    }
+}
+
+/// The system policy and the user policy. Every policy has to allow a package.
+pub fn get_policies() -> Result<Vec<(PathBuf, Policy)>, Box<dyn Error>> {
+    let mut paths = vec![PathBuf::from(SYSTEM_POLICY_PATH)];
+
+    if let Some(config_dir) = dirs::config_dir() {
+        paths.push(config_dir.join("aurcat").join("policy.toml"));
+    }
+
+    let mut policies: Vec<(PathBuf, Policy)> = vec![];
+
+    for path in paths {
+        if !path.exists() {
+            continue;
+        }
+
+        let content = fs::read_to_string(&path)?;
+        let policy: Policy = toml::from_str(&content)
+            .map_err(|error| format!("Invalid policy {}: {error}", path.display()))?;
+
+        policies.push((path, policy));
+    }
+
+    return Ok(policies);
+}
+
+/// The first rule that blocks the package, if any
+pub fn check_policy(policies: &[(PathBuf, Policy)], subject: &PolicySubject) -> Option<Violation> {
+    for (path, policy) in policies {
+        if let Some(rule) = get_blocking_rule(policy, subject) {
+            return Some(Violation {
+                package: subject.name.to_string(),
+                rule,
+                path: path.to_owned(),
+            });
+        }
+    }
+
+    return None;
+}
+
+/// Shows every package of the AUR builds the policies block. Returns an error if there is any.
+pub fn enforce_policy(builds: &[AurBuild]) -> Result<(), Box<dyn Error>> {
+    let policies = get_policies()?;
+
+    if policies.is_empty() {
+        return Ok(());
+    }
+
+    let violations: Vec<Violation> = builds
+        .iter()
+        .filter(|build| !build.local)
+        .flat_map(|build| {
+            // Every split package of the package base and the package base itself are checked
+            let mut names: Vec<&str> = build.pkgnames.iter().map(|name| name.as_str()).collect();
+
+            if !names.contains(&build.info.package_base.as_str()) {
+                names.push(&build.info.package_base);
+            }
+
+            names
+                .into_iter()
+                .filter_map(|name| {
+                    let subject = PolicySubject {
+                        name,
+                        ..PolicySubject::from(&build.info)
+                    };
+
+                    check_policy(&policies, &subject)
+                })
+                .collect::<Vec<Violation>>()
+        })
+        .collect();
+
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    for violation in &violations {
+        show_message(violation.to_string());
+    }
+
+    return Err("Blocked by the policy".into());
+}
+
+fn get_blocking_rule(policy: &Policy, subject: &PolicySubject) -> Option<String> {
+    if let Some(pattern) = find_match(&policy.deny.packages, subject.name) {
+        return Some(format!("deny.packages \"{pattern}\""));
+    }
+
+    if let Some(maintainer) = subject.maintainer
+        && let Some(pattern) = find_match(&policy.deny.maintainers, maintainer)
+    {
+        return Some(format!("deny.maintainers \"{pattern}\""));
+    }
+
+    if let Some(licenses) = subject.licenses {
+        for license in licenses {
+            if let Some(pattern) = find_match(&policy.deny.licenses, license) {
+                return Some(format!("deny.licenses \"{pattern}\""));
+            }
+        }
+    }
+
+    if !policy.allow.packages.is_empty()
+        && find_match(&policy.allow.packages, subject.name).is_none()
+    {
+        return Some("allow.packages (no pattern matches)".to_string());
+    }
+
+    if !policy.allow.maintainers.is_empty() {
+        let allowed = subject
+            .maintainer
+            .is_some_and(|maintainer| find_match(&policy.allow.maintainers, maintainer).is_some());
+
+        if !allowed {
+            return Some(format!(
+                "allow.maintainers (maintainer {})",
+                subject.maintainer.unwrap_or("nobody")
+            ));
+        }
+    }
+
+    // Every license of the package has to be allowed
+    if !policy.allow.licenses.is_empty()
+        && let Some(licenses) = subject.licenses
+    {
+        if licenses.is_empty() {
+            return Some("allow.licenses (no license)".to_string());
+        }
+
+        if let Some(license) = licenses
+            .iter()
+            .find(|license| find_match(&policy.allow.licenses, license).is_none())
+        {
+            return Some(format!("allow.licenses (license {license})"));
+        }
+    }
+
+    return None;
+}
+
+fn find_match<'a>(patterns: &'a [String], value: &str) -> Option<&'a String> {
+    return patterns.iter().find(|pattern| glob_matches(pattern, value));
+}
+
+/// Matches `*` to any text and `?` to any character
+fn glob_matches(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+
+    let (mut pattern_index, mut value_index) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while value_index < value.len() {
+        match pattern.get(pattern_index) {
+            Some('*') => {
+                backtrack = Some((pattern_index, value_index));
+                pattern_index += 1;
+            }
+            Some(c) if *c == '?' || *c == value[value_index] => {
+                pattern_index += 1;
+                value_index += 1;
+            }
+            _ => match backtrack {
+                Some((star_index, star_value_index)) => {
+                    pattern_index = star_index + 1;
+                    value_index = star_value_index + 1;
+                    backtrack = Some((star_index, star_value_index + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    return pattern[pattern_index..].iter().all(|c| *c == '*');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(packages: &[&str], maintainers: &[&str], licenses: &[&str]) -> PolicyRules {
+        let strings = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+
+        return PolicyRules {
+            packages: strings(packages),
+            maintainers: strings(maintainers),
+            licenses: strings(licenses),
+        };
+    }
+
+    fn subject<'a>(
+        name: &'a str,
+        maintainer: Option<&'a str>,
+        licenses: Option<&'a [String]>,
+    ) -> PolicySubject<'a> {
+        return PolicySubject {
+            name,
+            maintainer,
+            licenses,
+        };
+    }
+
+    #[test]
+    fn matches_globs() {
+        assert!(glob_matches("foo", "foo"));
+        assert!(!glob_matches("foo", "foobar"));
+        assert!(glob_matches("foo*", "foobar"));
+        assert!(glob_matches("*", ""));
+        assert!(glob_matches("f?o", "foo"));
+        assert!(!glob_matches("f?o", "fo"));
+        assert!(glob_matches("*-git", "foo-bar-git"));
+        assert!(glob_matches("*o*o*", "foo-bar-zoo"));
+        assert!(glob_matches("a*b*c", "axxbyybzzc"));
+        assert!(!glob_matches("a*b*c", "axxbyybzz"));
+    }
+
+    #[test]
+    fn deny_wins_over_allow() {
+        let policy = Policy {
+            allow: rules(&["*"], &[], &[]),
+            deny: rules(&["evil-*"], &[], &[]),
+        };
+
+        assert_eq!(
+            get_blocking_rule(&policy, &subject("evil-git", None, None)),
+            Some("deny.packages \"evil-*\"".to_string())
+        );
+        assert_eq!(
+            get_blocking_rule(&policy, &subject("good", None, None)),
+            None
+        );
+    }
+
+    #[test]
+    fn empty_allow_list_allows_everything() {
+        let policy = Policy::default();
+
+        assert_eq!(
+            get_blocking_rule(&policy, &subject("anything", None, None)),
+            None
+        );
+    }
+
+    #[test]
+    fn non_empty_allow_list_blocks_the_rest() {
+        let policy = Policy {
+            allow: rules(&["foo-*"], &[], &[]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            get_blocking_rule(&policy, &subject("foo-bin", None, None)),
+            None
+        );
+        assert_eq!(
+            get_blocking_rule(&policy, &subject("bar", None, None)),
+            Some("allow.packages (no pattern matches)".to_string())
+        );
+    }
+
+    #[test]
+    fn orphans_are_blocked_by_allowed_maintainers() {
+        let policy = Policy {
+            allow: rules(&[], &["trusted"], &[]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            get_blocking_rule(&policy, &subject("foo", Some("trusted"), None)),
+            None
+        );
+        assert_eq!(
+            get_blocking_rule(&policy, &subject("foo", None, None)),
+            Some("allow.maintainers (maintainer nobody)".to_string())
+        );
+    }
+
+    #[test]
+    fn missing_license_is_blocked_by_allowed_licenses() {
+        let policy = Policy {
+            allow: rules(&[], &[], &["MIT", "GPL*"]),
+            ..Default::default()
+        };
+        let allowed = vec!["MIT".to_string(), "GPL-3.0-or-later".to_string()];
+        let proprietary = vec!["MIT".to_string(), "custom".to_string()];
+
+        assert_eq!(
+            get_blocking_rule(&policy, &subject("foo", None, Some(&allowed))),
+            None
+        );
+        assert_eq!(
+            get_blocking_rule(&policy, &subject("foo", None, Some(&[]))),
+            Some("allow.licenses (no license)".to_string())
+        );
+        assert_eq!(
+            get_blocking_rule(&policy, &subject("foo", None, Some(&proprietary))),
+            Some("allow.licenses (license custom)".to_string())
+        );
+
+        // Search results don't know the licenses
+        assert_eq!(
+            get_blocking_rule(&policy, &subject("foo", None, None)),
+            None
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use std::error::Error;
+
+use colored::Colorize;
+use convert_case::{Case, Casing};
+use inquire::Select;
+use reqwest::Url;
+
+use crate::{
+    config::get_config,
+    list::get_all_packages,
+    search::AurSearchResponse,
+    srcinfo::dependency_name,
+    utils::{prompt_confirm, run_hidden, show_message},
+};
+
+#[derive(Debug, Clone)]
+pub struct Provider {
+    /// The repository of the provider or `aur`
+    pub repo: String,
+    pub package: String,
+    pub version: String,
+    pub installed: bool,
+}
+
+impl Provider {
+    pub fn is_aur(&self) -> bool {
+        return self.repo == "aur";
+    }
+}
+
+/// Every package in the sync databases and the AUR that provides the name.
+/// Installed providers come first, followed by the repositories in priority order and the AUR.
+pub async fn get_providers(
+    name: &str,
+    include_repos: bool,
+) -> Result<Vec<Provider>, Box<dyn Error>> {
+    let mut providers: Vec<Provider> = vec![];
+
+    if include_repos {
+        providers.append(&mut get_repo_providers(name)?);
+    }
+
+    providers.append(&mut get_aur_providers(name).await?);
+
+    let installed_packages = get_all_packages()?;
+
+    for provider in &mut providers {
+        provider.installed = installed_packages
+            .iter()
+            .any(|query| query.package == provider.package);
+    }
+
+    let config = get_config()?;
+
+    providers.sort_by_key(|provider| {
+        (
+            !provider.installed,
+            provider.is_aur(),
+            config.repo_rank(&provider.repo),
+        )
+    });
+
+    return Ok(providers);
+}
+
+/// Lets the user pick a provider and shows the chosen one.
+/// A single provider is picked without a choice, but an AUR package with another name has to be confirmed.
+pub fn pick_provider(
+    name: &str,
+    providers: Vec<Provider>,
+) -> Result<Option<Provider>, Box<dyn Error>> {
+    if providers.len() <= 1 {
+        let Some(provider) = providers.into_iter().next() else {
+            return Ok(None);
+        };
+
+        show_provider(name, &provider);
+
+        if provider.is_aur()
+            && provider.package != name
+            && !prompt_confirm("Would you like to use it?", true)?
+        {
+            return Ok(None);
+        }
+
+        return Ok(Some(provider));
+    }
+
+    let options: Vec<String> = providers
+        .iter()
+        .map(|provider| {
+            format!(
+                "{} {} {}{}",
+                if provider.is_aur() {
+                    "AUR".blue().bold()
+                } else {
+                    provider.repo.to_case(Case::Upper).green().bold()
+                },
+                provider.package.bold(),
+                provider.version,
+                if provider.installed {
+                    " (installed)"
+                } else {
+                    ""
+                }
+            )
+        })
+        .collect();
+
+    let result = Select::new(
+        &format!("There are multiple providers for {name}. Which one would you like to use?"),
+        options,
+    )
+    .with_page_size(15)
+    .raw_prompt_skippable()?;
+
+    let provider = result.and_then(|option| providers.into_iter().nth(option.index));
+
+    if let Some(provider) = &provider {
+        show_provider(name, provider);
+    }
+
+    return Ok(provider);
+}
+
+fn show_provider(name: &str, provider: &Provider) {
+    let repo = if provider.is_aur() {
+        "the AUR".to_string()
+    } else {
+        provider.repo.to_owned()
+    };
+
+    show_message(format!(
+        "{name} is provided by {} {} from {repo}",
+        &provider.package, &provider.version
+    ));
+}
+
+/// Reads the provides of every package in the sync databases
+fn get_repo_providers(name: &str) -> Result<Vec<Provider>, Box<dyn Error>> {
+    let repos_output = run_hidden(&["pacman-conf", "--repo-list"])?;
+    let db_path = run_hidden(&["pacman-conf", "DBPath"])?;
+    let db_path = match db_path.trim() {
+        "" => "/var/lib/pacman/",
+        db_path => db_path,
+    };
+
+    let mut providers: Vec<Provider> = vec![];
+
+    for repo in repos_output.lines().map(|line| line.trim()) {
+        if repo.is_empty() {
+            continue;
+        }
+
+        let db_file = format!("{}/sync/{repo}.db", db_path.trim_end_matches('/'));
+        let output = run_hidden(&["tar", "-xOf", &db_file, "--wildcards", "*/desc"])?;
+
+        let mut section = "";
+        let mut package = "";
+        let mut version = "";
+
+        for line in output.lines() {
+            if line.starts_with('%') && line.ends_with('%') {
+                section = line;
+                continue;
+            }
+
+            if line.is_empty() {
+                continue;
+            }
+
+            match section {
+                "%NAME%" => package = line,
+                "%VERSION%" => version = line,
+                "%PROVIDES%"
+                    if dependency_name(line) == name
+                        && !providers.iter().any(|provider| {
+                            provider.repo == repo && provider.package == package
+                        }) =>
+                {
+                    providers.push(Provider {
+                        repo: repo.to_string(),
+                        package: package.to_string(),
+                        version: version.to_string(),
+                        installed: false,
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    return Ok(providers);
+}
+
+async fn get_aur_providers(name: &str) -> Result<Vec<Provider>, Box<dyn Error>> {
+    let url = Url::parse_with_params(
+        "https://aur.archlinux.org/rpc/",
+        &[
+            ("v", "5"),
+            ("type", "search"),
+            ("by", "provides"),
+            ("arg", name),
+        ],
+    )?;
+
+    let response_json = reqwest::get(url).await?.text().await?;
+    let response: AurSearchResponse = serde_json::from_str(&response_json)?;
+
+    let providers: Vec<Provider> = response
+        .results
+        .into_iter()
+        .map(|result| Provider {
+            repo: "aur".to_string(),
+            package: result.name,
+            version: result.version,
+            installed: false,
+        })
+        .collect();
+
+    return Ok(providers);
+}
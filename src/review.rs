@@ -0,0 +1,231 @@
+use std::{error::Error, fs, path::Path};
+
+use colored::Colorize;
+use inquire::Select;
+
+use crate::{
+    analyzer::{Finding, analyze_build, show_findings},
+    approvals::{Approval, approve_snapshot, get_approval, is_approved},
+    config::get_config,
+    dependencies::AurBuild,
+    git::{get_repo_files, show_diff},
+    install::get_package_file_name,
+    srcinfo::SrcInfo,
+    trust::get_trust_warnings,
+    utils::{prompt_confirm, run, run_in_path, show_message},
+};
+
+/// Asks to review the build unless the snapshot was already approved.
+/// Approved snapshots that changed only show the changes since the approval.
+/// Builds with suspicious findings or trust warnings are always reviewed if the config forces it.
+pub fn prompt_review(build: &mut AurBuild, review: bool) -> Result<bool, Box<dyn Error>> {
+    let config = get_config()?;
+    let findings = analyze_build(build)?;
+    let has_trust_warnings = !build.local && !get_trust_warnings(&build.info, &config).is_empty();
+
+    let forced = (config.force_review_on_findings && !findings.is_empty())
+        || (config.force_review_on_trust_warnings && has_trust_warnings);
+
+    if !review && !forced {
+        return Ok(true);
+    }
+
+    let approval = get_approval(&build.info.package_base)?;
+
+    if let Some(approval) = &approval
+        && is_approved(build, approval)?
+    {
+        show_message(format!(
+            "{} is unchanged since it was approved",
+            &build.info.package_base
+        ));
+
+        return Ok(true);
+    }
+
+    if forced {
+        show_message(format!(
+            "{} has suspicious findings or trust warnings and has to be reviewed",
+            &build.info.package_base
+        ));
+
+        return review_package(build, approval.as_ref(), &findings);
+    }
+
+    if prompt_confirm(
+        &format!("Would you like to review {}?", &build.info.package_base),
+        true,
+    )? {
+        return review_package(build, approval.as_ref(), &findings);
+    }
+
+    return Ok(true);
+}
+
+fn review_package(
+    build: &mut AurBuild,
+    approval: Option<&Approval>,
+    findings: &[Finding],
+) -> Result<bool, Box<dyn Error>> {
+    if !findings.is_empty() {
+        show_findings(findings);
+    }
+
+    let approved_commit = approval.and_then(|approval| approval.commit.as_ref());
+
+    match (approved_commit, &build.installed_commit) {
+        (Some(commit), _) => {
+            show_message(format!(
+                "Changes since the approved commit {}",
+                commit.chars().take(8).collect::<String>()
+            ));
+
+            show_diff(&build.path, commit)?;
+        }
+        (None, Some(installed_commit)) => {
+            show_message(format!(
+                "Changes since the installed commit {}",
+                installed_commit.chars().take(8).collect::<String>()
+            ));
+
+            show_diff(&build.path, installed_commit)?;
+        }
+        (None, None) => {
+            view_file(build.path.join("PKGBUILD"))?;
+        }
+    }
+
+    review_files(build)?;
+
+    loop {
+        let options = vec!["Install", "Edit before building", "Cancel"];
+        let result = Select::new("What would you like to do?", options).prompt_skippable()?;
+
+        match result {
+            Some("Install") => {
+                approve_snapshot(build)?;
+                return Ok(true);
+            }
+            Some("Edit before building") => edit_snapshot(build)?,
+            _ => return Ok(false),
+        }
+    }
+}
+
+/// Opens the snapshot files in the editor. The build uses the edited files,
+/// so the `.SRCINFO` is generated again and the packages are rebuilt.
+fn edit_snapshot(build: &mut AurBuild) -> Result<(), Box<dyn Error>> {
+    let mut files = get_build_files(build)?;
+    files.sort_by_key(|file| (file != "PKGBUILD", file.clone()));
+
+    let mut command = get_config()?.editor_command();
+    command.extend(files);
+
+    run_in_path(&command, &build.path)?;
+
+    build.srcinfo = SrcInfo::generate(&build.path)?;
+    build.edited = true;
+
+    return Ok(());
+}
+
+/// Opens the file with the configured viewer
+pub fn view_file<P: AsRef<Path>>(path: P) -> Result<(), Box<dyn Error>> {
+    let mut command = get_config()?.viewer_command();
+    command.push(path.as_ref().display().to_string());
+
+    run(&command)?;
+
+    return Ok(());
+}
+
+/// Lets the user open every file of the snapshot until they are done
+fn review_files(build: &AurBuild) -> Result<(), Box<dyn Error>> {
+    let install_files = get_install_files(build);
+    let mut files = get_build_files(build)?;
+
+    // Files that run at install time go first since they need the most attention
+    files.sort_by_key(|file| {
+        (
+            !install_files.contains(file),
+            file != "PKGBUILD",
+            file.clone(),
+        )
+    });
+
+    for file in files.iter().filter(|file| install_files.contains(file)) {
+        show_message(format!("{} runs at install time", file.red().bold()));
+    }
+
+    let mut options: Vec<String> = files
+        .iter()
+        .map(|file| {
+            if install_files.contains(file) {
+                format!("⚠️ {file} (runs at install time)")
+            } else {
+                file.to_owned()
+            }
+        })
+        .collect();
+
+    options.push("Done".to_string());
+
+    let mut cursor = 0;
+
+    loop {
+        let result = Select::new("What file would you like to open?", options.clone())
+            .with_starting_cursor(cursor)
+            .with_page_size(15)
+            .raw_prompt_skippable()?;
+
+        let Some(option) = result else {
+            return Ok(());
+        };
+
+        let Some(file) = files.get(option.index) else {
+            return Ok(());
+        };
+
+        view_file(build.path.join(file))?;
+
+        cursor = option.index;
+    }
+}
+
+/// The files of the package build, without the build leftovers
+pub fn get_build_files(build: &AurBuild) -> Result<Vec<String>, Box<dyn Error>> {
+    if !build.local {
+        return get_repo_files(&build.path);
+    }
+
+    let mut files: Vec<String> = vec![];
+
+    for entry in fs::read_dir(&build.path)? {
+        let path = entry?.path();
+
+        if !path.is_file() || get_package_file_name(&path).is_some() {
+            continue;
+        }
+
+        if let Some(file_name) = path.file_name() {
+            files.push(file_name.to_string_lossy().to_string());
+        }
+    }
+
+    return Ok(files);
+}
+
+/// Scripts declared with `install=` that pacman runs when installing the packages
+pub fn get_install_files(build: &AurBuild) -> Vec<String> {
+    let mut install_files = build.srcinfo.values("install");
+
+    for pkgname in build.srcinfo.pkgnames() {
+        for file in build.srcinfo.package_values(pkgname, "install") {
+            if !install_files.contains(&file) {
+                install_files.push(file);
+            }
+        }
+    }
+
+    return install_files;
+}
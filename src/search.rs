@@ -1,13 +1,16 @@
-use std::error::Error;
+use std::{collections::HashMap, error::Error};
 
 use colored::Colorize;
 use convert_case::{Case, Casing};
 use inquire::{CustomType, validator::Validation};
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    config::get_config,
+    cli::SearchBy,
+    config::{Config, get_config},
     install::{install_aur_package, install_from_query},
+    policy::{PolicySubject, check_policy, get_policies},
     utils::{run_hidden, show_message},
 };
 
@@ -32,6 +35,7 @@ pub struct AurSearchQuery {
     pub description: String,
     pub url_path: String,
     pub out_of_date: bool,
+    pub maintainer: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -42,6 +46,9 @@ pub struct AurSearchResult {
     #[serde(rename = "LastModified")]
     pub last_modified: usize,
 
+    #[serde(rename = "Maintainer")]
+    pub maintainer: Option<String>,
+
     #[serde(rename = "Name")]
     pub name: String,
 
@@ -55,22 +62,38 @@ pub struct AurSearchResult {
     pub version: String,
 }
 
+impl AurSearchQuery {
+    pub fn policy_subject(&self) -> PolicySubject<'_> {
+        return PolicySubject {
+            name: &self.package,
+            maintainer: self.maintainer.as_deref(),
+            licenses: None,
+        };
+    }
+}
+
+/// Searches the repositories and the AUR. Searching by an AUR field skips the repositories.
 pub async fn on_search_command(
     package: String,
+    by: Option<SearchBy>,
     install_mode: bool,
     review: Option<bool>,
+    chroot: Option<bool>,
 ) -> Result<(), Box<dyn Error>> {
-    let review = match review {
-        Some(review) => review,
-        None => false,
-    };
+    let review = review.unwrap_or_default();
 
-    let max_results = get_config()?.max_results;
+    let config = get_config()?;
+    let chroot = chroot.unwrap_or(config.chroot);
+    let max_results = config.max_results;
 
-    let repo_packages = search_repo_packages(&package)?;
+    let mut repo_packages = match by {
+        Some(_) => vec![],
+        None => search_repo_packages(&package)?,
+    };
+    sort_by_repo_priority(&mut repo_packages, &config);
     let repo_packages_len = repo_packages.len();
 
-    let aur_packages = search_aur_packages(&package).await?;
+    let aur_packages = search_aur_packages(&package, by).await?;
     let aur_packages_len = aur_packages.len();
 
     let cut_repo_packages = repo_packages[0..(if repo_packages_len >= max_results {
@@ -95,9 +118,9 @@ pub async fn on_search_command(
         println!(
             "{}{} {} {}\n{}\n",
             if install_mode {
-                &format!("{} | ", index + 1).bold()
+                format!("{} | ", index + 1).bold()
             } else {
-                ""
+                "".normal()
             },
             &query.repo.to_case(Case::Upper).green().bold(),
             &query.package.bold(),
@@ -110,21 +133,30 @@ pub async fn on_search_command(
         println!("🌍 AUR Packages\n");
     }
 
+    let policies = get_policies()?;
+
     for (index, query) in cut_aur_packages.iter().enumerate() {
+        let blocked = check_policy(&policies, &query.policy_subject()).is_some();
+
         println!(
-            "{}{} {} {}\n{}\n",
+            "{}{} {} {}{}\n{}\n",
             if install_mode {
-                &format!("{} | ", cut_repo_packages.len() + index + 1).bold()
+                format!("{} | ", cut_repo_packages.len() + index + 1).bold()
             } else {
-                ""
+                "".normal()
             },
             "AUR".blue().bold(),
             if query.out_of_date {
-                &query.package.red().bold()
+                query.package.red().bold()
             } else {
-                &query.package.bold()
+                query.package.bold()
             },
             query.version.bold(),
+            if blocked {
+                " (blocked by policy)".red()
+            } else {
+                "".normal()
+            },
             &query.description
         );
     }
@@ -168,10 +200,15 @@ pub async fn on_search_command(
                     install_from_query(query)?;
                 } else {
                     let query = cut_aur_packages
-                        .get(index - 1)
+                        .get(index - cut_repo_packages.len() - 1)
                         .ok_or_else(|| "Error getting package".to_string())?;
 
-                    install_aur_package(&query.package, review).await?;
+                    if let Some(violation) = check_policy(&policies, &query.policy_subject()) {
+                        show_message(violation.to_string());
+                        return Ok(());
+                    }
+
+                    install_aur_package(&query.package, review, chroot).await?;
                 };
             }
             None => return Ok(()),
@@ -181,6 +218,24 @@ pub async fn on_search_command(
     Ok(())
 }
 
+/// Keeps the search order but lists the preferred repository first when a package exists on multiple ones
+pub fn sort_by_repo_priority(queries: &mut [SearchQuery], config: &Config) {
+    let mut first_positions: HashMap<String, usize> = HashMap::new();
+
+    for (index, query) in queries.iter().enumerate() {
+        first_positions
+            .entry(query.package.to_owned())
+            .or_insert(index);
+    }
+
+    queries.sort_by_key(|query| {
+        (
+            first_positions.get(&query.package).copied(),
+            config.repo_rank(&query.repo),
+        )
+    });
+}
+
 pub fn search_repo_packages(package: &str) -> Result<Vec<SearchQuery>, Box<dyn Error>> {
     let output = run_hidden(&["pacman", "-Ss", package])?;
     let mut search_queries: Vec<SearchQuery> = vec![];
@@ -189,19 +244,15 @@ pub fn search_repo_packages(package: &str) -> Result<Vec<SearchQuery>, Box<dyn E
 
     for chunk in output_split.chunks_exact(2) {
         let info = chunk
-            .get(0)
+            .first()
             .ok_or_else(|| "Failed to get info".to_string())?;
 
-        let description = chunk.get(1).ok_or_else(|| "Failed to get description")?;
+        let description = chunk.get(1).ok_or("Failed to get description")?;
 
-        let info_parts: Vec<String> = info
-            .split_whitespace()
-            .into_iter()
-            .map(|s| s.to_string())
-            .collect();
+        let info_parts: Vec<String> = info.split_whitespace().map(|s| s.to_string()).collect();
 
         let repo_package_string = info_parts
-            .get(0)
+            .first()
             .ok_or_else(|| "Failed to get repo package split".to_string())?;
 
         let repo_package_parts: Vec<String> = repo_package_string
@@ -210,7 +261,7 @@ pub fn search_repo_packages(package: &str) -> Result<Vec<SearchQuery>, Box<dyn E
             .collect();
 
         let repo = repo_package_parts
-            .get(0)
+            .first()
             .ok_or_else(|| "Failed to get repo".to_string())?;
 
         let package_name = repo_package_parts
@@ -232,8 +283,17 @@ pub fn search_repo_packages(package: &str) -> Result<Vec<SearchQuery>, Box<dyn E
     Ok(search_queries)
 }
 
-pub async fn search_aur_packages(package: &str) -> Result<Vec<AurSearchQuery>, Box<dyn Error>> {
-    let url = format!("https://aur.archlinux.org/rpc/?v=5&type=search&arg={package}");
+pub async fn search_aur_packages(
+    package: &str,
+    by: Option<SearchBy>,
+) -> Result<Vec<AurSearchQuery>, Box<dyn Error>> {
+    let mut params = vec![("v", "5"), ("type", "search"), ("arg", package)];
+
+    if let Some(by) = &by {
+        params.push(("by", by.rpc_field()));
+    }
+
+    let url = Url::parse_with_params("https://aur.archlinux.org/rpc/", &params)?;
 
     let response_json = reqwest::get(url).await?.text().await?;
     let response: AurSearchResponse = serde_json::from_str(&response_json)?;
@@ -252,6 +312,7 @@ pub async fn search_aur_packages(package: &str) -> Result<Vec<AurSearchQuery>, B
             },
             url_path: result.url_path,
             out_of_date: result.out_of_date.is_some(),
+            maintainer: result.maintainer,
         })
         .collect();
 
@@ -0,0 +1,207 @@
+use std::{
+    env::consts::ARCH,
+    error::Error,
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    process, thread,
+    time::Duration,
+};
+
+use crate::{
+    config::get_config,
+    dependencies::AurBuild,
+    git::has_local_changes,
+    utils::{get_checksum, show_message},
+};
+
+/// sha256sum compatible checksums of the package files in the shared cache
+const MANIFEST_FILE: &str = "SHA256SUMS";
+
+/// Held by the machine that updates the manifest
+const LOCK_FILE: &str = "SHA256SUMS.lock";
+
+/// How often and how long to wait for another machine to finish publishing
+const LOCK_ATTEMPTS: usize = 120;
+const LOCK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Removes the lock file when dropped, so an error while publishing doesn't keep the cache locked
+struct ManifestLock {
+    path: PathBuf,
+}
+
+impl Drop for ManifestLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Package files in the shared cache for every requested package of the build with the AUR version.
+/// Files with a checksum that doesn't match the manifest are ignored.
+pub fn find_cached_packages(build: &AurBuild) -> Result<Option<Vec<PathBuf>>, Box<dyn Error>> {
+    // Local package builds don't have to match the AUR
+    if build.local {
+        return Ok(None);
+    }
+
+    let Some(cache_dir) = get_config()?.shared_cache_dir() else {
+        return Ok(None);
+    };
+
+    if !cache_dir.exists() {
+        return Ok(None);
+    }
+
+    let manifest = read_manifest(&cache_dir)?;
+    let mut cached_files: Vec<PathBuf> = vec![];
+
+    for pkgname in &build.pkgnames {
+        let prefixes = [
+            format!("{pkgname}-{}-{ARCH}.pkg.tar", &build.info.version),
+            format!("{pkgname}-{}-any.pkg.tar", &build.info.version),
+        ];
+
+        let cached_file = manifest
+            .iter()
+            .find(|(_, file)| prefixes.iter().any(|prefix| file.starts_with(prefix)));
+
+        let Some((checksum, file)) = cached_file else {
+            return Ok(None);
+        };
+
+        let path = cache_dir.join(file);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        if &get_checksum(&path)? != checksum {
+            show_message(format!("Checksum mismatch for {file} in the shared cache"));
+            return Ok(None);
+        }
+
+        cached_files.push(path);
+    }
+
+    return Ok(Some(cached_files));
+}
+
+/// Copies the package files of the build to the shared cache and records their checksums.
+/// Only unmodified AUR snapshots are published since other machines trust them to match the AUR.
+pub fn publish_packages(build: &AurBuild, package_files: &[PathBuf]) -> Result<(), Box<dyn Error>> {
+    if build.local || build.edited {
+        return Ok(());
+    }
+
+    let Some(cache_dir) = get_config()?.shared_cache_dir() else {
+        return Ok(());
+    };
+
+    if has_local_changes(&build.path)? {
+        return Ok(());
+    }
+
+    fs::create_dir_all(&cache_dir)?;
+
+    // Other machines can publish at the same time, so the manifest is read and merged under the lock
+    let _lock = lock_manifest(&cache_dir)?;
+    let mut manifest = read_manifest(&cache_dir)?;
+    let mut changed = false;
+
+    for file in package_files {
+        let Some(file_name) = file
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+        else {
+            continue;
+        };
+
+        let checksum = get_checksum(file)?;
+
+        if manifest
+            .iter()
+            .any(|entry| entry == &(checksum.clone(), file_name.clone()))
+        {
+            continue;
+        }
+
+        fs::copy(file, cache_dir.join(&file_name))?;
+
+        manifest.retain(|(_, manifest_file)| manifest_file != &file_name);
+        manifest.push((checksum, file_name));
+        changed = true;
+    }
+
+    if changed {
+        show_message("Published Packages to the Shared Cache");
+        write_manifest(&cache_dir, &manifest)?;
+    }
+
+    return Ok(());
+}
+
+/// Creates the lock file, waiting while another machine holds it
+fn lock_manifest(cache_dir: &Path) -> Result<ManifestLock, Box<dyn Error>> {
+    let path = cache_dir.join(LOCK_FILE);
+
+    for _ in 0..LOCK_ATTEMPTS {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                let lock = ManifestLock { path };
+                writeln!(file, "{}", get_owner())?;
+
+                return Ok(lock);
+            }
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => thread::sleep(LOCK_INTERVAL),
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    let owner = fs::read_to_string(&path).unwrap_or_default();
+
+    return Err(format!(
+        "The shared cache is locked by {}, remove {} if nothing is publishing",
+        owner.trim(),
+        path.display()
+    )
+    .into());
+}
+
+/// The host and process, unique across the machines sharing the cache
+fn get_owner() -> String {
+    let host = fs::read_to_string("/proc/sys/kernel/hostname").unwrap_or_default();
+
+    return format!("{}.{}", host.trim(), process::id());
+}
+
+fn read_manifest(cache_dir: &Path) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    let manifest_path = cache_dir.join(MANIFEST_FILE);
+
+    if !manifest_path.exists() {
+        return Ok(vec![]);
+    }
+
+    let content = fs::read_to_string(&manifest_path)?;
+
+    return Ok(content
+        .lines()
+        .filter_map(|line| {
+            let (checksum, file) = line.split_once("  ")?;
+            Some((checksum.to_string(), file.to_string()))
+        })
+        .collect());
+}
+
+fn write_manifest(cache_dir: &Path, manifest: &[(String, String)]) -> Result<(), Box<dyn Error>> {
+    let content: String = manifest
+        .iter()
+        .map(|(checksum, file)| format!("{checksum}  {file}\n"))
+        .collect();
+
+    // Written to a temporary file first so other machines never read a partial manifest
+    let temp_path = cache_dir.join(format!("{MANIFEST_FILE}.{}.tmp", get_owner()));
+    fs::write(&temp_path, content)?;
+    fs::rename(&temp_path, cache_dir.join(MANIFEST_FILE))?;
+
+    return Ok(());
+}
@@ -0,0 +1,247 @@
+use std::{collections::HashMap, env::consts::ARCH, error::Error, fs, path::Path};
+
+use crate::utils::run_hidden_in_path;
+
+/// Parsed `.SRCINFO` of a package build
+#[derive(Debug, Clone, Default)]
+pub struct SrcInfo {
+    /// Values from the `pkgbase` section
+    pub base: HashMap<String, Vec<String>>,
+
+    /// Each `pkgname` section with the values it overrides
+    pub packages: Vec<(String, HashMap<String, Vec<String>>)>,
+}
+
+impl SrcInfo {
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let srcinfo_path = path.as_ref().join(".SRCINFO");
+
+        let content = fs::read_to_string(&srcinfo_path)
+            .map_err(|e| format!("Failed to read {}: {e}", srcinfo_path.display()))?;
+
+        return Ok(Self::parse(&content));
+    }
+
+    /// Generates the `.SRCINFO` from the PKGBUILD, for builds without one or with an edited PKGBUILD
+    pub fn generate<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let output = run_hidden_in_path(&["makepkg", "--printsrcinfo"], path)?;
+
+        return Ok(Self::parse(&output));
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let mut srcinfo = SrcInfo::default();
+
+        for line in content.lines() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+
+            let key = key.trim().to_string();
+            let value = value.trim().to_string();
+
+            if key == "pkgname" {
+                srcinfo.packages.push((value, HashMap::new()));
+                continue;
+            }
+
+            let section = match srcinfo.packages.last_mut() {
+                Some((_, section)) => section,
+                None => &mut srcinfo.base,
+            };
+
+            let values = section.entry(key).or_default();
+
+            // An empty value inside a package section clears the pkgbase value
+            if !value.is_empty() {
+                values.push(value);
+            }
+        }
+
+        return srcinfo;
+    }
+
+    pub fn pkgbase(&self) -> &str {
+        return self
+            .base
+            .get("pkgbase")
+            .and_then(|values| values.first())
+            .map(|value| value.as_str())
+            .unwrap_or_default();
+    }
+
+    pub fn pkgnames(&self) -> Vec<&str> {
+        return self
+            .packages
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+    }
+
+    /// Full version in the `epoch:pkgver-pkgrel` format
+    pub fn version(&self) -> String {
+        let pkgver = self.values("pkgver").join("");
+        let pkgrel = self.values("pkgrel").join("");
+
+        return match self.values("epoch").first() {
+            Some(epoch) => format!("{epoch}:{pkgver}-{pkgrel}"),
+            None => format!("{pkgver}-{pkgrel}"),
+        };
+    }
+
+    /// Values from the pkgbase section, including the ones for the current architecture
+    pub fn values(&self, key: &str) -> Vec<String> {
+        return get_arch_keys(key)
+            .iter()
+            .flat_map(|key| self.base.get(key).cloned().unwrap_or_default())
+            .collect();
+    }
+
+    /// Values for a package, falling back to pkgbase when the package doesn't override them.
+    /// The key and the key for the current architecture are overridden separately.
+    pub fn package_values(&self, pkgname: &str, key: &str) -> Vec<String> {
+        let section = self
+            .packages
+            .iter()
+            .find(|(name, _)| name == pkgname)
+            .map(|(_, section)| section);
+
+        return get_arch_keys(key)
+            .iter()
+            .flat_map(|key| {
+                section
+                    .and_then(|section| section.get(key))
+                    .or_else(|| self.base.get(key))
+                    .cloned()
+                    .unwrap_or_default()
+            })
+            .collect();
+    }
+
+    /// Dependencies needed to build and install the given packages
+    pub fn dependencies(&self, pkgnames: &[&str]) -> Vec<String> {
+        let mut dependencies: Vec<String> = vec![];
+
+        let mut add = |values: Vec<String>| {
+            for value in values {
+                if !dependencies.contains(&value) {
+                    dependencies.push(value);
+                }
+            }
+        };
+
+        for pkgname in pkgnames {
+            add(self.package_values(pkgname, "depends"));
+        }
+
+        add(self.values("makedepends"));
+        add(self.values("checkdepends"));
+
+        return dependencies;
+    }
+}
+
+/// The key and its variant for the current architecture like `depends_x86_64`
+fn get_arch_keys(key: &str) -> [String; 2] {
+    return [key.to_string(), format!("{key}_{ARCH}")];
+}
+
+/// Strips the version constraint of a dependency. For example `foo>=1.0` becomes `foo`
+pub fn dependency_name(dependency: &str) -> &str {
+    return dependency
+        .split(['<', '>', '='])
+        .next()
+        .unwrap_or(dependency);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_example() -> SrcInfo {
+        return SrcInfo::parse(&format!(
+            "pkgbase = example
+	pkgver = 1.2
+	pkgrel = 3
+	epoch = 1
+	makedepends = cmake
+	depends = base-dep
+	depends_{ARCH} = base-arch-dep
+
+pkgname = example-a
+
+pkgname = example-b
+	depends =
+
+pkgname = example-c
+	depends_{ARCH} = c-arch-dep
+"
+        ));
+    }
+
+    #[test]
+    fn parses_split_sections() {
+        let srcinfo = parse_example();
+
+        assert_eq!(srcinfo.pkgbase(), "example");
+        assert_eq!(
+            srcinfo.pkgnames(),
+            vec!["example-a", "example-b", "example-c"]
+        );
+        assert_eq!(srcinfo.version(), "1:1.2-3");
+    }
+
+    #[test]
+    fn falls_back_to_pkgbase_values() {
+        let srcinfo = parse_example();
+
+        assert_eq!(
+            srcinfo.package_values("example-a", "depends"),
+            vec!["base-dep", "base-arch-dep"]
+        );
+    }
+
+    #[test]
+    fn empty_value_clears_pkgbase_value() {
+        let srcinfo = parse_example();
+
+        assert_eq!(
+            srcinfo.package_values("example-b", "depends"),
+            vec!["base-arch-dep"]
+        );
+    }
+
+    #[test]
+    fn arch_key_is_overridden_separately() {
+        let srcinfo = parse_example();
+
+        assert_eq!(
+            srcinfo.package_values("example-c", "depends"),
+            vec!["base-dep", "c-arch-dep"]
+        );
+    }
+
+    #[test]
+    fn collects_dependencies_of_the_packages() {
+        let srcinfo = parse_example();
+
+        assert_eq!(
+            srcinfo.dependencies(&["example-b", "example-c"]),
+            vec!["base-arch-dep", "base-dep", "c-arch-dep", "cmake"]
+        );
+    }
+
+    #[test]
+    fn strips_version_constraints() {
+        assert_eq!(dependency_name("foo>=1.0"), "foo");
+        assert_eq!(dependency_name("foo<2"), "foo");
+        assert_eq!(dependency_name("foo=1.0-1"), "foo");
+        assert_eq!(dependency_name("foo"), "foo");
+    }
+}
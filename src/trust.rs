@@ -0,0 +1,97 @@
+use std::{
+    error::Error,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use colored::Colorize;
+
+use crate::{
+    config::{Config, get_config},
+    dependencies::AurBuild,
+    install::AurPackageInfoResult,
+    utils::show_message,
+};
+
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+/// Shows who maintains the AUR packages and how popular they are, with warnings for risky packages
+pub fn show_trust_summary(builds: &[AurBuild]) -> Result<(), Box<dyn Error>> {
+    let config = get_config()?;
+    let aur_builds: Vec<&AurBuild> = builds.iter().filter(|build| !build.local).collect();
+
+    if aur_builds.is_empty() {
+        return Ok(());
+    }
+
+    show_message("Trust Summary:");
+
+    for build in aur_builds {
+        let info = &build.info;
+
+        println!(
+            "{} {} by {}, {} votes, {:.2} popularity, submitted {} days ago",
+            info.package_base.bold(),
+            info.version,
+            info.maintainer.as_deref().unwrap_or("nobody"),
+            info.num_votes,
+            info.popularity,
+            get_days_since(info.first_submitted)
+        );
+
+        if let Some(url) = &info.url {
+            println!("    {url}");
+        }
+
+        if let Some(licenses) = info
+            .license
+            .as_ref()
+            .filter(|licenses| !licenses.is_empty())
+        {
+            println!("    {}", licenses.join(", "));
+        }
+
+        for warning in get_trust_warnings(info, &config) {
+            println!("    {} {}", "⚠️".yellow(), warning.yellow());
+        }
+    }
+
+    Ok(())
+}
+
+/// Warnings for orphaned, out of date and new packages with few votes
+pub fn get_trust_warnings(info: &AurPackageInfoResult, config: &Config) -> Vec<String> {
+    let mut warnings: Vec<String> = vec![];
+
+    if info.maintainer.is_none() {
+        warnings.push("The package is orphaned".to_string());
+    }
+
+    if let Some(out_of_date) = info.out_of_date {
+        warnings.push(format!(
+            "Flagged out of date {} days ago",
+            get_days_since(out_of_date)
+        ));
+    }
+
+    let days_since_submission = get_days_since(info.first_submitted);
+
+    if days_since_submission < config.trust_new_package_days
+        && info.num_votes < config.trust_min_votes
+    {
+        warnings.push(format!(
+            "Submitted {days_since_submission} days ago and only has {} votes",
+            info.num_votes
+        ));
+    }
+
+    return warnings;
+}
+
+fn get_days_since(timestamp: usize) -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    return now.saturating_sub(timestamp as u64) / SECONDS_PER_DAY;
+}
@@ -1,6 +1,6 @@
 use std::error::Error;
 
-use crate::{list::get_aur_packages, utils::run};
+use crate::{list::get_aur_packages, utils::run_checked};
 
 pub fn on_uninstall_command(packages: Vec<String>, confirm: bool) -> Result<(), Box<dyn Error>> {
     uninstall_packages(&packages, confirm)?;
@@ -18,7 +18,7 @@ pub fn uninstall_packages<S: AsRef<str>>(
         let package = package.as_ref().to_string();
         let debug_package = format!("{package}-debug");
 
-        if aur_packages.iter().any(|ap| &ap.package == &debug_package) {
+        if aur_packages.iter().any(|ap| ap.package == debug_package) {
             command.push(debug_package);
         }
 
@@ -29,7 +29,7 @@ pub fn uninstall_packages<S: AsRef<str>>(
         command.push("--noconfirm".to_string());
     }
 
-    run(&command)?;
+    run_checked(&command)?;
 
     Ok(())
 }
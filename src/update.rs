@@ -1,15 +1,20 @@
-use std::error::Error;
+use std::{collections::HashMap, error::Error, process::Command};
+
+use reqwest::Url;
 
 use crate::{
-    install::{AurPackageInfoResponse, install_aur_package},
+    config::get_config,
+    install::{AurPackageInfoResponse, install_aur_packages},
     list::get_aur_packages,
-    utils::run,
+    policy::{PolicySubject, check_policy, get_policies},
+    utils::{run_checked, show_message},
 };
 
 pub async fn on_update_command(
     aur: bool,
     review: bool,
     confirm: bool,
+    chroot: bool,
 ) -> Result<(), Box<dyn Error>> {
     update_repo_packages(confirm)?;
 
@@ -17,45 +22,124 @@ pub async fn on_update_command(
         return Ok(());
     }
 
-    update_aur_packages(review).await?;
+    update_aur_packages(review, chroot).await?;
 
     Ok(())
 }
 
 pub fn update_repo_packages(confirm: bool) -> Result<(), Box<dyn Error>> {
+    let config = get_config()?;
+
+    if config.repo_priority.is_empty() {
+        let mut command = vec![
+            "sudo".to_string(),
+            "pacman".to_string(),
+            "-Syyu".to_string(),
+        ];
+
+        if !confirm {
+            command.push("--noconfirm".to_string());
+        }
+
+        run_checked(&command)?;
+
+        return Ok(());
+    }
+
+    run_checked(&["sudo", "pacman", "-Syy"])?;
+
     let mut command = vec![
         "sudo".to_string(),
         "pacman".to_string(),
-        "-Syyu".to_string(),
+        "-Su".to_string(),
+        "--needed".to_string(),
     ];
 
+    // Packages on multiple repos are passed explicitly so pacman takes them from the preferred one
+    for (package, repos) in get_installed_repo_packages()? {
+        if repos.len() < 2 {
+            continue;
+        }
+
+        if let Some(repo) = config.preferred_repo(&repos) {
+            command.push(format!("{repo}/{package}"));
+        }
+    }
+
     if !confirm {
         command.push("--noconfirm".to_string());
     }
 
-    run(&command)?;
+    run_checked(&command)?;
 
     Ok(())
 }
 
-pub async fn update_aur_packages(review: bool) -> Result<(), Box<dyn Error>> {
-    let mut url = "https://aur.archlinux.org/rpc/?v=5&type=info".to_string();
-    let packages = get_aur_packages(true)?;
+/// Installed packages with every repository that has them, in pacman.conf order
+fn get_installed_repo_packages() -> Result<HashMap<String, Vec<String>>, Box<dyn Error>> {
+    let command = Command::new("pacman")
+        .arg("-Sl")
+        .env("LC_ALL", "C")
+        .output()?;
 
-    for package in &packages {
-        url = format!("{url}&arg[]={}", package.package);
+    let output = String::from_utf8(command.stdout)?;
+    let mut packages: HashMap<String, Vec<String>> = HashMap::new();
+
+    for line in output.lines() {
+        if !line.contains("[installed") {
+            continue;
+        }
+
+        let mut splitted = line.split_whitespace();
+
+        let (Some(repo), Some(package)) = (splitted.next(), splitted.next()) else {
+            continue;
+        };
+
+        packages
+            .entry(package.to_string())
+            .or_default()
+            .push(repo.to_string());
     }
 
-    let response_json = reqwest::get(&url).await?.text().await?;
+    return Ok(packages);
+}
+
+pub async fn update_aur_packages(review: bool, chroot: bool) -> Result<(), Box<dyn Error>> {
+    let packages = get_aur_packages(true)?;
+
+    let mut params = vec![("v", "5"), ("type", "info")];
+    params.extend(
+        packages
+            .iter()
+            .map(|package| ("arg[]", package.package.as_str())),
+    );
+
+    let url = Url::parse_with_params("https://aur.archlinux.org/rpc/", &params)?;
+
+    let response_json = reqwest::get(url).await?.text().await?;
     let response: AurPackageInfoResponse = serde_json::from_str(&response_json)?;
 
+    let policies = get_policies()?;
+    let mut outdated_packages: Vec<String> = vec![];
+
     for result in &response.results {
         for package in &packages {
             if result.name == package.package && result.version != package.version {
-                install_aur_package(&package.package, review).await?;
+                // Blocked packages are skipped so the others still update
+                if let Some(violation) = check_policy(&policies, &PolicySubject::from(result)) {
+                    show_message(format!("Skipping update: {violation}"));
+                    continue;
+                }
+
+                outdated_packages.push(package.package.to_owned());
             }
         }
     }
 
+    if !outdated_packages.is_empty() {
+        install_aur_packages(&outdated_packages, review, chroot).await?;
+    }
+
     Ok(())
 }
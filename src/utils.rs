@@ -4,11 +4,15 @@ use std::{
     process::{Command, Stdio},
 };
 
-use inquire::ui::{RenderConfig, Styled};
+use inquire::{
+    Text,
+    ui::{RenderConfig, Styled},
+    validator::Validation,
+};
 use strip_ansi_escapes::strip;
 
 pub fn run_hidden(command: &[&str]) -> Result<String, Box<dyn Error>> {
-    let main = command.get(0).ok_or_else(|| "Empty Vector".to_string())?;
+    let main = command.first().ok_or_else(|| "Empty Vector".to_string())?;
 
     let args: Vec<String> = command
         .iter()
@@ -39,7 +43,7 @@ pub fn run_hidden_in_path<P: AsRef<Path>>(
     command: &[&str],
     path: P,
 ) -> Result<String, Box<dyn Error>> {
-    let main = command.get(0).ok_or_else(|| "Empty Vector".to_string())?;
+    let main = command.first().ok_or_else(|| "Empty Vector".to_string())?;
 
     let args: Vec<String> = command
         .iter()
@@ -69,7 +73,7 @@ pub fn run_hidden_in_path<P: AsRef<Path>>(
 
 pub fn run<S: AsRef<str>>(command: &[S]) -> Result<String, Box<dyn Error>> {
     let main = command
-        .get(0)
+        .first()
         .ok_or_else(|| "Empty Vector".to_string())?
         .as_ref();
 
@@ -105,7 +109,7 @@ pub fn run_in_path<S: AsRef<str>, P: AsRef<Path>>(
     path: P,
 ) -> Result<String, Box<dyn Error>> {
     let main = command
-        .get(0)
+        .first()
         .ok_or_else(|| "Empty Vector".to_string())?
         .as_ref();
 
@@ -137,12 +141,98 @@ pub fn run_in_path<S: AsRef<str>, P: AsRef<Path>>(
     return Ok(output);
 }
 
+/// Runs the command visibly and fails if it exits with an error, for example when pacman is declined
+pub fn run_checked<S: AsRef<str>>(command: &[S]) -> Result<(), Box<dyn Error>> {
+    return run_checked_command(command, None);
+}
+
+pub fn run_checked_in_path<S: AsRef<str>, P: AsRef<Path>>(
+    command: &[S],
+    path: P,
+) -> Result<(), Box<dyn Error>> {
+    return run_checked_command(command, Some(path.as_ref()));
+}
+
+fn run_checked_command<S: AsRef<str>>(
+    command: &[S],
+    path: Option<&Path>,
+) -> Result<(), Box<dyn Error>> {
+    let main = command
+        .first()
+        .ok_or_else(|| "Empty Vector".to_string())?
+        .as_ref();
+
+    let args: Vec<&str> = command.iter().skip(1).map(|arg| arg.as_ref()).collect();
+
+    let mut process = Command::new(main);
+    process
+        .args(&args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit());
+
+    if let Some(path) = path {
+        process.current_dir(path);
+    }
+
+    let status = process.status()?;
+
+    if !status.success() {
+        let command: Vec<&str> = command.iter().map(|arg| arg.as_ref()).collect();
+        return Err(format!("{} failed with {status}", command.join(" ")).into());
+    }
+
+    return Ok(());
+}
+
+/// The sha256 checksum of the file
+pub fn get_checksum(path: &Path) -> Result<String, Box<dyn Error>> {
+    let output = run_hidden(&["sha256sum", &path.display().to_string()])?;
+
+    let checksum = output
+        .split_whitespace()
+        .next()
+        .ok_or_else(|| format!("Failed to get the checksum of {}", path.display()))?;
+
+    return Ok(checksum.to_string());
+}
+
 pub fn show_message<S: AsRef<str>>(message: S) {
     println!("😺 {}", message.as_ref());
 }
 
+/// Prompts a yes or no question. An empty answer uses the default.
+pub fn prompt_confirm(message: &str, default: bool) -> Result<bool, Box<dyn Error>> {
+    let result = Text::new(&format!(
+        "{message} {}\n",
+        if default { "(Y/n)" } else { "(y/N)" }
+    ))
+    .with_validator(move |input: &str| {
+        let lower_answer = input.to_lowercase().to_string();
+
+        match lower_answer.as_str() {
+            "y" => Ok(Validation::Valid),
+            "yes" => Ok(Validation::Valid),
+            "n" => Ok(Validation::Valid),
+            "no" => Ok(Validation::Valid),
+            _ => Ok(Validation::Invalid("Invalid Answer".into())),
+        }
+    })
+    .with_default(if default { "Y" } else { "N" })
+    .prompt();
+
+    let answer = result?.to_lowercase();
+
+    return match answer.as_str() {
+        "y" => Ok(true),
+        "yes" => Ok(true),
+        _ => Ok(false),
+    };
+}
+
 pub fn get_empty_render_config() -> RenderConfig<'static> {
-    let mut render_config = RenderConfig::default();
-    render_config.prompt_prefix = Styled::new("");
-    render_config
+    RenderConfig {
+        prompt_prefix: Styled::new(""),
+        ..Default::default()
+    }
 }